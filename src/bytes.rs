@@ -1,19 +1,29 @@
 use crate::bytes::composite::write_u24;
-use crate::{eof, Buffered, Result, ToSlice};
+use crate::decode::{Decode, DecodeResult};
+use crate::error::{buffer_error, BufferError, Error};
+use crate::{Buffered, Result, ToSlice};
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::mem;
+use core::ops::{Deref, Index, Range, RangeFrom, RangeInclusive, RangeTo};
+#[cfg(feature = "std")]
 use std::io;
-use std::mem;
-use std::ops::{Deref, Index, RangeFrom, RangeInclusive, RangeTo};
 
-use self::composite::read_u24;
+use self::composite::{crc32, read_i24_le, read_u24, write_i24_le};
 
 macro_rules! impl_get_bytes {
     ($buf:ident, $byte_ty:ty, $conversion:expr) => {{
         const SIZE: usize = mem::size_of::<$byte_ty>();
-        let limit = $buf.len();
+        let limit = $buf.end();
         let pos = $buf.pos();
         if pos + SIZE > limit {
             log::debug!("pos: {}, size: {}, limit: {}", pos, SIZE, limit);
-            return Err(io::ErrorKind::UnexpectedEof.into());
+            return Err(BufferError::Eof {
+                needed: SIZE,
+                available: limit.saturating_sub(pos),
+            }
+            .into_error());
         }
 
         let slice = unsafe { *($buf.bytes[pos..pos + SIZE].as_ptr() as *const [_; SIZE]) };
@@ -28,174 +38,1105 @@ macro_rules! impl_put_bytes {
         let slice_len = $value.len();
         let buf_len = $this.bytes.len();
         if pos + slice_len >= buf_len {
-            $this.bytes.resize(buf_len * 2, 0u8);
+            let new_len = usize::max(buf_len * 2, pos + slice_len);
+            $this.buffer.grow_to(new_len);
+            $this.bytes.resize(new_len, 0u8);
         }
 
         $this.bytes[pos..pos + slice_len].copy_from_slice($value);
+        $this.buffer.filled = usize::max($this.buffer.filled, pos + slice_len);
+        $this.last_span = Some(pos..pos + slice_len);
         $this.advance_index(slice_len);
     }};
 }
 
+macro_rules! impl_try_get {
+    ($($name:ident => $get:ident: $ty:ty),* $(,)?) => {
+        $(
+            #[doc = concat!("Attempts to return a `", stringify!($ty), "` as [", stringify!($get), "](Buffered::", stringify!($get), ") does, but reports")]
+            /// [Incomplete](DecodeResult::Incomplete) rather than an error when too few bytes remain. The position is only
+            /// incremented once the value is complete. A read stopped by the logical end set with [limit](Buffered::limit)
+            /// is an error, as no further input can complete it.
+            pub fn $name(&mut self) -> DecodeResult<$ty> {
+                let result = self.$get();
+                self.decode_result(result)
+            }
+        )*
+    };
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct Bytes {
     bytes: Vec<u8>,
+    /// The length of the real data within `bytes`, being the greater of the initial contents and the furthest write.
+    /// Anything beyond this is padding left behind by growth.
+    filled: usize,
 }
 
 impl Bytes {
     /// Constructs a new byte buffer using the provided vector as the initial contents.
     pub fn new(contents: Vec<u8>) -> Self {
-        Self { bytes: contents }
+        Self {
+            filled: contents.len(),
+            bytes: contents,
+        }
+    }
+
+    /// Constructs a new byte buffer holding each of `words` serialized in big-endian order.
+    pub fn from_u16_be(words: &[u16]) -> Self {
+        Self::new(words.iter().flat_map(|word| word.to_be_bytes()).collect())
+    }
+
+    /// Constructs a new byte buffer holding each of `words` serialized in big-endian order.
+    pub fn from_u32_be(words: &[u32]) -> Self {
+        Self::new(words.iter().flat_map(|word| word.to_be_bytes()).collect())
+    }
+
+    /// Returns the number of bytes the backing vector can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.bytes.capacity()
+    }
+
+    /// Ensures the backing vector can hold at least `capacity` bytes. With the `zeroize` feature, growing moves the
+    /// contents into a fresh allocation and wipes the old one, rather than letting the allocator free it unwiped.
+    fn grow_to(&mut self, capacity: usize) {
+        if capacity <= self.bytes.capacity() {
+            return;
+        }
+
+        #[cfg(feature = "zeroize")]
+        {
+            let mut grown = Vec::with_capacity(usize::max(self.bytes.capacity() * 2, capacity));
+            grown.extend_from_slice(&self.bytes);
+            zeroize::Zeroize::zeroize(&mut self.bytes);
+            self.bytes = grown;
+        }
+        #[cfg(not(feature = "zeroize"))]
+        self.bytes.reserve(capacity - self.bytes.len());
+    }
+}
+
+/// The width, in bytes, of a length slot reserved by [reserve_var_len](Buffered::reserve_var_len).
+const VAR_LEN_WIDTH: usize = 5;
+
+/// A length slot reserved within a buffer, to be filled once the length of the section following it is known.
+#[must_use = "a reserved length slot should be filled with `patch_var_len`"]
+#[derive(Debug)]
+pub struct VarLenPlaceholder {
+    pos: usize,
+}
+
+/// A borrowed view over a byte slice, allowing a [Buffered] cursor to walk memory it does not own.
+#[derive(Clone, Copy, Debug)]
+pub struct BytesRef<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> BytesRef<'a> {
+    /// Constructs a new view over the provided slice.
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes }
     }
 }
 
 impl Buffered<Bytes> {
+    /// Creates an [Eof](BufferError::Eof) error for an operation requiring `needed` bytes from the cursor.
+    fn eof_error(&self, needed: usize) -> Error {
+        BufferError::Eof {
+            needed,
+            available: self.remaining(),
+        }
+        .into_error()
+    }
+
+    /// Runs the read `f`, upholding the contract shared by every `get_*` method: if `f` fails, the cursor and
+    /// [last span](Buffered::last_span) are restored to what they were before `f` ran, and if it succeeds, the bytes it
+    /// consumed are recorded as the last span and forwarded to the [tap](Buffered::with_tap). The tap is detached while
+    /// `f` runs so that reads composed of other reads forward their bytes only once.
+    fn read_atomic<R, F>(&mut self, f: F) -> Result<R>
+    where
+        F: FnOnce(&mut Self) -> Result<R>,
+    {
+        let pos = self.pos;
+        let last_span = self.last_span.take();
+        let mut tap = self.tap.take();
+        let result = f(self);
+        if result.is_err() {
+            self.pos = pos;
+            self.last_span = last_span;
+        } else {
+            self.last_span = Some(pos..self.pos);
+            if let (Some(tap), Some(consumed)) = (tap.as_mut(), self.buffer.bytes.get(pos..self.pos)) {
+                tap(consumed);
+            }
+        }
+        self.tap = tap;
+        result
+    }
+
+    /// Forwards the bytes within `range` to the [tap](Buffered::with_tap), for cursor moves made outside of
+    /// [read_atomic](Buffered::read_atomic).
+    fn forward_to_tap(&mut self, range: Range<usize>) {
+        if let (Some(tap), Some(consumed)) = (self.tap.as_mut(), self.buffer.bytes.get(range)) {
+            tap(consumed);
+        }
+    }
+
+    /// Runs the write `f`, recording the bytes it wrote as the [last span](Buffered::last_span). Writes composed of other
+    /// writes use this so that the span covers the whole value rather than its final piece.
+    fn write_spanned<F>(&mut self, f: F) -> &mut Self
+    where
+        F: FnOnce(&mut Self) -> &mut Self,
+    {
+        let pos = self.pos;
+        f(self);
+        self.last_span = Some(pos..self.pos);
+        self
+    }
+
+    /// Converts the result of a read from the cursor into a [DecodeResult]. Running out of bytes is only reported as
+    /// [Incomplete](DecodeResult::Incomplete) if the bytes needed would fit before any logical end set with
+    /// [limit](Buffered::limit); past that, more input cannot help and the error is returned as is.
+    fn decode_result<R>(&self, result: Result<R>) -> DecodeResult<R> {
+        let past_limit = |err: &Error| match (self.limit, buffer_error(err)) {
+            (Some(limit), Some(BufferError::Eof { needed, .. })) => self.pos.saturating_add(*needed) > limit,
+            _ => false,
+        };
+        match result {
+            Err(err) if past_limit(&err) => DecodeResult::Error(err),
+            result => result.into(),
+        }
+    }
+
+    /// Returns the bytes between the cursor and the end of the buffer.
+    fn tail(&self) -> &[u8] {
+        self.buffer.bytes.get(self.pos..self.end()).unwrap_or_default()
+    }
+
+    /// Installs `tap` to receive the bytes consumed by every successful `get_*` and `read_frame*` call, along with those
+    /// passed over by [seek_to_byte](Buffered::seek_to_byte) and [take_while](Buffered::take_while), in order, replacing
+    /// any existing tap. This allows wire traffic to be logged without changing the call sites that parse it. Moving the
+    /// cursor directly, such as with [set_position](Buffered::set_position) or [advance_index](Buffered::advance_index),
+    /// is not logged. The tap is not carried over when the buffer is cloned, and must be [Sync] so that the buffer
+    /// remains shareable between threads.
+    pub fn with_tap<F>(&mut self, tap: F) -> &mut Self
+    where
+        F: FnMut(&[u8]) + Send + Sync + 'static,
+    {
+        self.tap = Some(Box::new(tap));
+        self
+    }
+
+    /// Removes the tap installed by [with_tap](Buffered::with_tap), if any.
+    pub fn clear_tap(&mut self) {
+        self.tap = None;
+    }
+
     /// Returns an immutable reference to the underlying byte slice.
     pub fn bytes(&self) -> &[u8] {
         &self.buffer.bytes
     }
 
+    /// Returns the real data held by the buffer: the initial contents extended by anything written past them, without the
+    /// zeroed padding left behind when the buffer grows. This is the preferred accessor over [bytes](Buffered::bytes),
+    /// which exposes the entire backing storage.
+    pub fn contents(&self) -> &[u8] {
+        &self.buffer.bytes[..self.buffer.filled]
+    }
+
+    /// Reinterprets the [contents](Buffered::contents) of the buffer as a slice of `T` without copying. Returns `None` if
+    /// the contents are not suitably aligned for `T` or their length is not a multiple of its size.
+    ///
+    /// The values are read in native byte order, unlike the big-endian `get_*` methods.
+    #[cfg(feature = "bytemuck")]
+    pub fn as_slice_of<T: bytemuck::Pod>(&self) -> Option<&[T]> {
+        bytemuck::try_cast_slice(self.contents()).ok()
+    }
+
+    /// Returns `true` if both buffers hold the same [contents](Buffered::contents), regardless of where their cursors sit.
+    pub fn content_eq(&self, other: &Buffered<Bytes>) -> bool {
+        self.contents() == other.contents()
+    }
+
+    /// Consumes the buffer, returning the underlying [Bytes] truncated to its [contents](Buffered::contents) so that no
+    /// growth padding is handed on to consumers.
+    pub fn finalize(self) -> Bytes {
+        let mut buffer = self.buffer;
+        buffer.bytes.truncate(buffer.filled);
+        buffer
+    }
+
+    /// Returns a borrowed view over the bytes from the cursor to the end of the buffer. With the `nom` feature enabled,
+    /// the view may be handed straight to nom parsers; advance the cursor by the amount they consumed afterwards.
+    pub fn remaining_ref(&self) -> BytesRef<'_> {
+        BytesRef::new(self.tail())
+    }
+
+    /// Consumes the buffer, returning a [Cursor](io::Cursor) over its [contents](Buffered::contents) positioned at the
+    /// current cursor position.
+    #[cfg(feature = "std")]
+    pub fn into_cursor(self) -> io::Cursor<Vec<u8>> {
+        let pos = self.pos as u64;
+        let mut cursor = io::Cursor::new(self.finalize().bytes);
+        cursor.set_position(pos);
+        cursor
+    }
+
     /// Returns a mutable reference to the underlying byte slice.
     pub fn bytes_mut(&mut self) -> &mut [u8] {
         &mut self.bytes
     }
 
+    /// Returns `len` bytes starting at the absolute `offset` without moving the cursor, or `None` if the range falls
+    /// outside of the buffer's [contents](Buffered::contents).
+    pub fn peek_bytes_at(&self, offset: usize, len: usize) -> Option<&[u8]> {
+        self.contents().get(offset..offset.checked_add(len)?)
+    }
+
+    /// Reads the `index`th big-endian `u32` entry of a table beginning at the absolute offset `table_base`, without moving
+    /// the cursor. Returns an error if the entry lies outside of the buffer's [contents](Buffered::contents).
+    pub fn read_u32_at_index(&self, table_base: usize, index: usize) -> Result<u32> {
+        let offset = index.checked_mul(4).and_then(|relative| relative.checked_add(table_base));
+        match offset.and_then(|offset| self.peek_bytes_at(offset, 4)) {
+            Some(entry) => Ok(u32::from_be_bytes([entry[0], entry[1], entry[2], entry[3]])),
+            None => Err(BufferError::Eof {
+                needed: 4,
+                available: offset.map_or(0, |offset| self.buffer.filled.saturating_sub(offset)),
+            }
+            .into_error()),
+        }
+    }
+
     /// Attempts to return an unsigned byte from the reader, incrementing the position by `1` if successful. Otherwise
     /// an error is returned if not enough bytes remain.
     pub fn get_u8(&mut self) -> Result<u8> {
-        impl_get_bytes!(self, u8, u8::from_be_bytes)
+        self.read_atomic(|buf| impl_get_bytes!(buf, u8, u8::from_be_bytes))
     }
 
     /// Attempts to return a signed byte from the reader, incrementing the position by `1` if successful. Otherwise
     /// an error is returned if not enough bytes remain.
     pub fn get_i8(&mut self) -> Result<i8> {
-        impl_get_bytes!(self, i8, i8::from_be_bytes)
+        self.read_atomic(|buf| impl_get_bytes!(buf, i8, i8::from_be_bytes))
     }
 
     /// Attempts to return a signed short from the reader, incrementing the position by `2` if successful. Otherwise
     /// an error is returned if not enough bytes remain.
     pub fn get_i16(&mut self) -> Result<i16> {
-        impl_get_bytes!(self, i16, i16::from_be_bytes)
+        self.read_atomic(|buf| impl_get_bytes!(buf, i16, i16::from_be_bytes))
     }
 
     /// Attempts to return an unsigned short from the reader, incrementing the position by `2` if successful. Otherwise
     /// an error is returned if not enough bytes remain.
     pub fn get_u16(&mut self) -> Result<u16> {
-        impl_get_bytes!(self, u16, u16::from_be_bytes)
+        self.read_atomic(|buf| impl_get_bytes!(buf, u16, u16::from_be_bytes))
     }
 
     /// Attempts to return a 24-bit unsigned integer from the reader, incrementing the position by `3` if successful. Otherwise
     /// an error is returned if not enough bytes remain.
     pub fn get_u24(&mut self) -> Result<usize> {
-        if self.is_available(3) {
-            let value = read_u24(&self.bytes[self.pos..self.pos + 3]);
-            self.advance_index(3);
-            Ok(value)
-        } else {
-            eof()
-        }
+        self.read_atomic(|buf| {
+            if buf.is_available(3) {
+                let value = read_u24(&buf.bytes[buf.pos..buf.pos + 3]);
+                buf.advance_index(3);
+                Ok(value)
+            } else {
+                Err(buf.eof_error(3))
+            }
+        })
+    }
+
+    /// Attempts to return a little-endian, 24-bit signed integer from the reader, incrementing the position by `3` if
+    /// successful. Otherwise an error is returned if not enough bytes remain.
+    pub fn get_i24_le(&mut self) -> Result<i32> {
+        self.read_atomic(|buf| {
+            if buf.is_available(3) {
+                let value = read_i24_le(&buf.bytes[buf.pos..buf.pos + 3]);
+                buf.advance_index(3);
+                Ok(value)
+            } else {
+                Err(buf.eof_error(3))
+            }
+        })
     }
 
     /// Attempts to return a signed integer from the reader, incrementing the position by `4` if successful. Otherwise
     /// an error is returned if not enough bytes remain.
     pub fn get_i32(&mut self) -> Result<i32> {
-        impl_get_bytes!(self, i32, i32::from_be_bytes)
+        self.read_atomic(|buf| impl_get_bytes!(buf, i32, i32::from_be_bytes))
     }
 
     /// Attempts to return an unsigned integer from the reader, incrementing the position by `4` if successful. Otherwise
     /// an error is returned if not enough bytes remain.
     pub fn get_u32(&mut self) -> Result<u32> {
-        impl_get_bytes!(self, u32, u32::from_be_bytes)
+        self.read_atomic(|buf| impl_get_bytes!(buf, u32, u32::from_be_bytes))
     }
 
     /// Attempts to return a signed long from the reader, incrementing the position by `8` if successful. Otherwise
     /// an error is returned if not enough bytes remain.
     pub fn get_i64(&mut self) -> Result<i64> {
-        impl_get_bytes!(self, i64, i64::from_be_bytes)
+        self.read_atomic(|buf| impl_get_bytes!(buf, i64, i64::from_be_bytes))
     }
 
     /// Attempts to return an unsigned long from the reader, incrementing the position by `8` if successful. Otherwise
     /// an error is returned if not enough bytes remain.
     pub fn get_u64(&mut self) -> Result<u64> {
-        impl_get_bytes!(self, u64, u64::from_be_bytes)
+        self.read_atomic(|buf| impl_get_bytes!(buf, u64, u64::from_be_bytes))
+    }
+
+    /// Attempts to return a little-endian, single-precision float from the reader, incrementing the position by `4` if
+    /// successful. Otherwise an error is returned if not enough bytes remain.
+    pub fn get_f32_le(&mut self) -> Result<f32> {
+        self.read_atomic(|buf| impl_get_bytes!(buf, f32, f32::from_le_bytes))
+    }
+
+    /// Attempts to return a little-endian, double-precision float from the reader, incrementing the position by `8` if
+    /// successful. Otherwise an error is returned if not enough bytes remain.
+    pub fn get_f64_le(&mut self) -> Result<f64> {
+        self.read_atomic(|buf| impl_get_bytes!(buf, f64, f64::from_le_bytes))
+    }
+
+    /// Attempts to return a byte from the reader unpacked into its individual bits, incrementing the position by `1` if
+    /// successful. Bits are ordered most-significant first, so index `0` holds the bit `0x80`. Otherwise an error is
+    /// returned if not enough bytes remain.
+    pub fn get_flags8(&mut self) -> Result<[bool; 8]> {
+        let byte = self.get_u8()?;
+        let mut flags = [false; 8];
+        for (i, flag) in flags.iter_mut().enumerate() {
+            *flag = byte & (0x80 >> i) != 0;
+        }
+        Ok(flags)
+    }
+
+    /// Attempts to return `n` signed bytes from the reader, incrementing the position by `n` if successful. Otherwise an
+    /// error is returned if not enough bytes remain.
+    pub fn get_i8_slice(&mut self, n: usize) -> Result<Vec<i8>> {
+        self.read_atomic(|buf| {
+            if !buf.is_available(n) {
+                return Err(buf.eof_error(n));
+            }
+
+            let pos = buf.pos;
+            let values = buf.bytes[pos..pos + n].iter().map(|byte| *byte as i8).collect();
+            buf.pos += n;
+            Ok(values)
+        })
+    }
+
+    /// Attempts to return `N` big-endian unsigned shorts from the reader as a fixed-size array, incrementing the position
+    /// by `N * 2` if successful. Otherwise an error is returned if not enough bytes remain.
+    pub fn get_u16_array<const N: usize>(&mut self) -> Result<[u16; N]> {
+        self.read_atomic(|buf| {
+            let range = buf.bulk_range(N, 2)?;
+            let mut values = [0u16; N];
+            for (value, chunk) in values.iter_mut().zip(buf.buffer.bytes[range.clone()].chunks_exact(2)) {
+                *value = u16::from_be_bytes([chunk[0], chunk[1]]);
+            }
+            buf.pos = range.end;
+            Ok(values)
+        })
+    }
+
+    /// Attempts to return `count` big-endian single-precision floats from the reader, incrementing the position by
+    /// `count * 4` if successful. Otherwise an error is returned if not enough bytes remain or the read exceeds the
+    /// [allocation limit](Buffered::set_alloc_limit).
+    pub fn get_f32_slice(&mut self, count: usize) -> Result<Vec<f32>> {
+        self.read_atomic(|buf| {
+            let range = buf.bulk_range(count, 4)?;
+            let values = buf.bytes[range.clone()]
+                .chunks_exact(4)
+                .map(|chunk| f32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+                .collect();
+            buf.pos = range.end;
+            Ok(values)
+        })
+    }
+
+    /// Attempts to return `count` big-endian double-precision floats from the reader, incrementing the position by
+    /// `count * 8` if successful. Otherwise an error is returned if not enough bytes remain or the read exceeds the
+    /// [allocation limit](Buffered::set_alloc_limit).
+    pub fn get_f64_slice(&mut self, count: usize) -> Result<Vec<f64>> {
+        self.read_atomic(|buf| {
+            let range = buf.bulk_range(count, 8)?;
+            let values = buf.bytes[range.clone()]
+                .chunks_exact(8)
+                .map(|chunk| {
+                    let mut bytes = [0u8; 8];
+                    bytes.copy_from_slice(chunk);
+                    f64::from_be_bytes(bytes)
+                })
+                .collect();
+            buf.pos = range.end;
+            Ok(values)
+        })
+    }
+
+    /// Returns the byte range spanned by `count` items of `width` bytes from the cursor, after checking it against both
+    /// the allocation limit and the remaining bytes.
+    fn bulk_range(&self, count: usize, width: usize) -> Result<Range<usize>> {
+        let len = count.checked_mul(width).ok_or(BufferError::Overflow)?;
+        self.check_alloc(len)?;
+        if !self.is_available(len) {
+            return Err(self.eof_error(len));
+        }
+        Ok(self.pos..self.pos + len)
+    }
+
+    impl_try_get! {
+        try_get_u8 => get_u8: u8,
+        try_get_i8 => get_i8: i8,
+        try_get_u16 => get_u16: u16,
+        try_get_i16 => get_i16: i16,
+        try_get_u32 => get_u32: u32,
+        try_get_i32 => get_i32: i32,
+        try_get_u64 => get_u64: u64,
+        try_get_i64 => get_i64: i64,
+    }
+
+    /// Attempts to return exactly `n` bytes from the reader, incrementing the position by `n` if successful. Unlike a
+    /// partial `Read::read`, an [Eof](BufferError::Eof) error is returned if fewer than
+    /// `n` bytes remain, in which case nothing is consumed.
+    pub fn get_exact(&mut self, n: usize) -> Result<Vec<u8>> {
+        self.read_atomic(|buf| {
+            if !buf.is_available(n) {
+                return Err(buf.eof_error(n));
+            }
+
+            let pos = buf.pos;
+            buf.pos += n;
+            Ok(buf.bytes[pos..pos + n].to_vec())
+        })
+    }
+
+    /// Reads a frame prefixed by its big-endian `u32` length, returning a new buffer holding exactly the frame's bytes and
+    /// incrementing the position past the frame. An error is returned, leaving the position unchanged, if the frame is
+    /// incomplete or its length exceeds the [allocation limit](Buffered::set_alloc_limit). Convert the result into a
+    /// [DecodeResult] to tell an incomplete frame apart from a malformed one.
+    pub fn read_frame_u32(&mut self) -> Result<Buffered<Bytes>> {
+        self.read_atomic(|buf| {
+            let len = buf.get_u32()? as usize;
+            buf.check_alloc(len)?;
+            let frame = buf.get_exact(len)?;
+            Ok(Buffered::using(Bytes::new(frame)))
+        })
+    }
+
+    /// Attempts to return a payload of `n` bytes from the reader as a vector allocated to exactly that size, incrementing
+    /// the position by `n` if successful. Intended for lengths decoded from the input itself: the length is checked
+    /// against the remaining bytes and the [allocation limit](Buffered::set_alloc_limit) before anything is allocated, so
+    /// a corrupt length cannot trigger a large speculative allocation.
+    pub fn get_bytes_vec(&mut self, n: usize) -> Result<Vec<u8>> {
+        self.check_alloc(n)?;
+        self.get_exact(n)
+    }
+
+    /// Attempts to read a tuple of [decodable](Decode) values in sequence, such as `(u8, u32, u16)`, incrementing the
+    /// position by their combined width if successful. Otherwise an error is returned, leaving the position unchanged.
+    pub fn get_tuple<T: Decode>(&mut self) -> Result<T> {
+        self.read_atomic(|buf| T::decode(buf))
+    }
+
+    /// Reads a frame written by [write_frame](Buffered::write_frame), returning its payload and incrementing the position
+    /// past the trailing checksum. An error is returned, leaving the position unchanged, if the frame is incomplete, its
+    /// length exceeds the [allocation limit](Buffered::set_alloc_limit), or the checksum does not match.
+    pub fn read_frame(&mut self) -> Result<Vec<u8>> {
+        self.read_atomic(|buf| {
+            let pos = buf.pos;
+            let len = buf.get_u32()?;
+            let payload = buf.get_bytes_vec(len as usize)?;
+            let expected = buf.get_u32()?;
+            let actual = crc32(&buf.bytes[pos..pos + 4 + payload.len()]);
+            if expected != actual {
+                return Err(BufferError::ChecksumMismatch { expected, actual }.into_error());
+            }
+            Ok(payload)
+        })
+    }
+
+    /// Reads a checksummed section written by [write_checked_section_u32](Buffered::write_checked_section_u32): a big-endian
+    /// `u32` length, the payload, then a CRC-32 checksum covering the payload alone. Returns a new buffer holding exactly
+    /// the payload, incrementing the position past the checksum. An error is returned if the section is incomplete, its
+    /// length exceeds the [allocation limit](Buffered::set_alloc_limit), or the checksum does not match.
+    pub fn read_checked_section_u32(&mut self) -> Result<Buffered<Bytes>> {
+        self.read_atomic(|buf| {
+            let len = buf.get_u32()? as usize;
+            let payload = buf.get_bytes_vec(len)?;
+            let expected = buf.get_u32()?;
+            let actual = crc32(&payload);
+            if expected != actual {
+                return Err(BufferError::ChecksumMismatch { expected, actual }.into_error());
+            }
+            Ok(Buffered::using(Bytes::new(payload)))
+        })
     }
 
     /// Tries to read a null-terminated string (c-string) from the reader, returning an error if the operation could not complete. The reader
     /// position is incremented based on the width of the string read.
     pub fn get_str(&mut self) -> Result<String> {
+        self.read_atomic(|buf| {
+            let pos = buf.pos;
+            let index = buf.find_terminator()?;
+
+            String::from_utf8(buf.bytes[pos..index].to_vec())
+                .map(|str| {
+                    buf.pos += str.len() + 1;
+                    str
+                })
+                .map_err(|err| {
+                    BufferError::InvalidUtf8 {
+                        offset: pos + err.utf8_error().valid_up_to(),
+                    }
+                    .into_error()
+                })
+        })
+    }
+
+    /// Tries to read null-terminated strings back to back from the cursor until the buffer ends, returning an error if
+    /// any string is unterminated or not valid UTF-8. The position is left unchanged on failure.
+    pub fn get_str_list(&mut self) -> Result<Vec<String>> {
+        self.read_atomic(|buf| buf.read_str_list(false))
+    }
+
+    /// Tries to read null-terminated strings back to back from the cursor until an empty string, such that the list ends
+    /// in a double null, returning an error if the buffer ends first or any string is not valid UTF-8. The position is
+    /// incremented past the final null, or left unchanged on failure.
+    pub fn get_str_list_terminated(&mut self) -> Result<Vec<String>> {
+        self.read_atomic(|buf| buf.read_str_list(true))
+    }
+
+    /// Reads a list of null-terminated strings, stopping at the end of the buffer or, if `terminated`, an empty string.
+    fn read_str_list(&mut self, terminated: bool) -> Result<Vec<String>> {
+        let mut items = Vec::new();
+        while terminated || self.remaining() > 0 {
+            let item = self.get_str()?;
+            if terminated && item.is_empty() {
+                break;
+            }
+            items.push(item);
+        }
+        Ok(items)
+    }
+
+    /// Tries to read the raw content of a null-terminated string (c-string) from the reader without validating it as UTF-8,
+    /// returning an error if no terminator is found. The reader position is incremented past the terminator.
+    pub fn get_cstr_bytes(&mut self) -> Result<Vec<u8>> {
+        self.read_atomic(|buf| {
+            let pos = buf.pos;
+            let index = buf.find_terminator()?;
+            buf.pos = index + 1;
+            Ok(buf.bytes[pos..index].to_vec())
+        })
+    }
+
+    /// Advances the cursor to the next occurrence of `marker`, leaving it positioned on the marker itself. Returns an error
+    /// without moving the cursor if the marker does not appear in the remainder of the buffer.
+    pub fn seek_to_byte(&mut self, marker: u8) -> Result<()> {
+        match self.tail().iter().position(|byte| *byte == marker) {
+            Some(offset) => {
+                self.forward_to_tap(self.pos..self.pos + offset);
+                self.pos += offset;
+                Ok(())
+            }
+            None => Err(self.eof_error(self.remaining() + 1)),
+        }
+    }
+
+    /// Consumes the run of bytes from the cursor for which `pred` holds, returning them and incrementing the position by
+    /// the length of the run. An empty slice is returned if the byte at the cursor does not satisfy `pred`.
+    pub fn take_while<F: Fn(u8) -> bool>(&mut self, pred: F) -> &[u8] {
         let pos = self.pos;
-        let Some(index) = self.iter().position(|c| *c == 0) else {
-            return eof();
-        };
+        let len = self.tail().iter().position(|byte| !pred(*byte)).unwrap_or(self.remaining());
+        self.forward_to_tap(pos..pos + len);
+        self.pos += len;
+        &self.buffer.bytes[pos..pos + len]
+    }
+
+    /// Returns the absolute position of the next null terminator at or after the cursor.
+    fn find_terminator(&self) -> Result<usize> {
+        let pos = self.pos;
+        match self.tail().iter().position(|c| *c == 0) {
+            Some(offset) => Ok(pos + offset),
+            None => Err(self.eof_error(self.remaining() + 1)),
+        }
+    }
+
+    /// Attempts to return a LEB128-encoded unsigned integer from the reader, incrementing the position by the width of
+    /// the encoding (`1` to `5` bytes) if successful. Otherwise an error is returned if not enough bytes remain or the
+    /// encoding overflows a `u32`, leaving the position unchanged.
+    pub fn get_var_u32(&mut self) -> Result<u32> {
+        self.read_atomic(|buf| {
+            let mut value = 0u32;
+            for shift in (0..35).step_by(7) {
+                let byte = buf.get_u8()?;
+                if shift == 28 && byte & 0xF0 != 0 {
+                    break;
+                }
+                value |= ((byte & 0x7F) as u32) << shift;
+                if byte & 0x80 == 0 {
+                    return Ok(value);
+                }
+            }
+
+            Err(BufferError::Overflow.into_error())
+        })
+    }
+
+    /// Attempts to return a MIDI-style variable-length quantity from the reader, incrementing the position by the width of
+    /// the encoding if successful. Unlike LEB128, the most significant 7-bit group comes first. Otherwise an error is
+    /// returned if not enough bytes remain or the value overflows a `u32`, leaving the position unchanged.
+    pub fn get_midi_vlq(&mut self) -> Result<u32> {
+        self.read_atomic(|buf| {
+            let mut value = 0u32;
+            loop {
+                let byte = buf.get_u8()?;
+                if value > u32::MAX >> 7 {
+                    return Err(BufferError::Overflow.into_error());
+                }
+                value = (value << 7) | (byte & 0x7F) as u32;
+                if byte & 0x80 == 0 {
+                    return Ok(value);
+                }
+            }
+        })
+    }
+
+    /// Tries to read a UTF-8 string prefixed by its LEB128-encoded byte length, returning an error if the operation could
+    /// not complete or the length exceeds the [allocation limit](Buffered::set_alloc_limit). The position is left unchanged
+    /// on failure.
+    pub fn get_str_var(&mut self) -> Result<String> {
+        self.read_atomic(|buf| {
+            let len = buf.get_var_u32()? as usize;
+            buf.check_alloc(len)?;
+            if !buf.is_available(len) {
+                return Err(buf.eof_error(len));
+            }
+
+            let start = buf.pos;
+            match String::from_utf8(buf.bytes[start..start + len].to_vec()) {
+                Ok(str) => {
+                    buf.pos += len;
+                    Ok(str)
+                }
+                Err(err) => Err(BufferError::InvalidUtf8 {
+                    offset: start + err.utf8_error().valid_up_to(),
+                }
+                .into_error()),
+            }
+        })
+    }
+
+    /// Tries to read a UTF-8 string prefixed by its LEB128-encoded byte length as [get_str_var](Buffered::get_str_var)
+    /// does, but validates the payload in chunks as it is copied out rather than collecting it first. A large payload
+    /// with an early invalid byte fails without copying the rest, and the error reports the absolute offset of that byte.
+    /// The position is left unchanged on failure.
+    pub fn get_str_prefixed_validated(&mut self) -> Result<String> {
+        self.read_atomic(|buf| {
+            const CHUNK: usize = 4096;
+
+            let len = buf.get_var_u32()? as usize;
+            buf.check_alloc(len)?;
+            if !buf.is_available(len) {
+                return Err(buf.eof_error(len));
+            }
 
-        String::from_utf8(self.bytes[pos..index].to_vec())
-            .map(|str| {
-                self.pos += str.len() + 1;
-                str
-            })
-            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+            let start = buf.pos;
+            let end = start + len;
+            let mut str = String::with_capacity(len);
+            let mut offset = start;
+            while offset < end {
+                let chunk = &buf.buffer.bytes[offset..usize::min(offset + CHUNK, end)];
+                let valid = match core::str::from_utf8(chunk) {
+                    Ok(valid) => valid,
+                    // A sequence split across the chunk boundary is carried into the next chunk, unless the payload ends here.
+                    Err(err) if err.error_len().is_none() && offset + chunk.len() < end && err.valid_up_to() > 0 => {
+                        unsafe { core::str::from_utf8_unchecked(&chunk[..err.valid_up_to()]) }
+                    }
+                    Err(err) => {
+                        return Err(BufferError::InvalidUtf8 {
+                            offset: offset + err.valid_up_to(),
+                        }
+                        .into_error());
+                    }
+                };
+                str.push_str(valid);
+                offset += valid.len();
+            }
+
+            buf.pos = end;
+            Ok(str)
+        })
+    }
+
+    /// Tries to read an optional value prefixed by a LEB128-encoded discriminant, where `0` means `None` and `1` means a
+    /// value follows, which is read by `f`. Otherwise an error is returned if the discriminant is neither, not enough bytes
+    /// remain, or `f` fails, leaving the position unchanged.
+    pub fn get_optional<T, F>(&mut self, f: F) -> Result<Option<T>>
+    where
+        F: FnOnce(&mut Self) -> Result<T>,
+    {
+        self.read_atomic(|buf| {
+            match buf.get_var_u32()? {
+                0 => Ok(None),
+                1 => f(buf).map(Some),
+                discriminant => Err(BufferError::InvalidDiscriminant { value: discriminant }.into_error()),
+            }
+        })
     }
 
     /// Writes an unsigned byte value into the buffer, incrementing the position by `1`.
-    pub fn put_u8(&mut self, value: u8) {
+    pub fn put_u8(&mut self, value: u8) -> &mut Self {
         let slice = &u8::to_be_bytes(value);
         impl_put_bytes!(self, slice);
+        self
     }
 
     /// Writes a signed byte value into the buffer, incrementing the position by `1`.
-    pub fn put_i8(&mut self, value: i8) {
+    pub fn put_i8(&mut self, value: i8) -> &mut Self {
         let slice = &i8::to_be_bytes(value);
         impl_put_bytes!(self, slice);
+        self
     }
 
     /// Writes a signed short value into the buffer, incrementing the position by `2`.
-    pub fn put_i16(&mut self, value: i16) {
+    pub fn put_i16(&mut self, value: i16) -> &mut Self {
         let slice = &i16::to_be_bytes(value);
         impl_put_bytes!(self, slice);
+        self
     }
 
     /// Writes an unsigned short value into the buffer, incrementing the position by `2`.
-    pub fn put_u16(&mut self, value: u16) {
+    pub fn put_u16(&mut self, value: u16) -> &mut Self {
         let slice: &[u8; 2] = &u16::to_be_bytes(value);
         impl_put_bytes!(self, slice);
+        self
     }
 
-    pub fn put_u24(&mut self, value: u32) {
+    /// Writes a 24-bit unsigned value into the buffer, incrementing the position by `3`. Only the low 24 bits of `value`
+    /// are written; use [try_put_u24](Buffered::try_put_u24) to reject larger values instead.
+    pub fn put_u24(&mut self, value: u32) -> &mut Self {
         let slice = &write_u24(value);
         impl_put_bytes!(self, slice);
+        self
+    }
+
+    /// Writes a 24-bit unsigned value into the buffer, incrementing the position by `3` if successful. Otherwise an
+    /// [OutOfRange](BufferError::OutOfRange) error is returned if `value` exceeds `0xFFFFFF`, and nothing is written.
+    pub fn try_put_u24(&mut self, value: u32) -> Result<()> {
+        const MAX: u32 = 0xFF_FFFF;
+        if value > MAX {
+            return Err(BufferError::OutOfRange {
+                value: value as u64,
+                max: MAX as u64,
+            }
+            .into_error());
+        }
+        self.put_u24(value);
+        Ok(())
+    }
+
+    /// Writes a signed 24-bit value into the buffer in little-endian order, incrementing the position by `3`. Only the low
+    /// 24 bits of `value` are written.
+    pub fn put_i24_le(&mut self, value: i32) -> &mut Self {
+        let slice = &write_i24_le(value);
+        impl_put_bytes!(self, slice);
+        self
     }
 
     /// Writes a signed int value into the buffer, incrementing the position by `4`.
-    pub fn put_i32(&mut self, value: i32) {
+    pub fn put_i32(&mut self, value: i32) -> &mut Self {
         let slice = &i32::to_be_bytes(value);
         impl_put_bytes!(self, slice);
+        self
     }
 
     /// Writes an unsigned int value into the buffer, incrementing the position by `4`.
-    pub fn put_u32(&mut self, value: u32) {
+    pub fn put_u32(&mut self, value: u32) -> &mut Self {
         let slice = &u32::to_be_bytes(value);
         impl_put_bytes!(self, slice);
+        self
     }
 
     /// Writes an unsigned int value into the buffer, incrementing the position by `8`.
-    pub fn put_u64(&mut self, value: u64) {
+    pub fn put_u64(&mut self, value: u64) -> &mut Self {
         let slice = &u64::to_be_bytes(value);
         impl_put_bytes!(self, slice);
+        self
     }
 
-    /// Writes a null-terminated string value into the buffer, incremeneting the position by `value.len() + 1`.
-    pub fn put_str<S: AsRef<str>>(&mut self, value: S) {
-        let bytes: &[u8] = value.as_ref().as_bytes();
+    /// Writes a single-precision float value into the buffer in little-endian order, incrementing the position by `4`.
+    pub fn put_f32_le(&mut self, value: f32) -> &mut Self {
+        let slice = &f32::to_le_bytes(value);
+        impl_put_bytes!(self, slice);
+        self
+    }
+
+    /// Writes a double-precision float value into the buffer in little-endian order, incrementing the position by `8`.
+    pub fn put_f64_le(&mut self, value: f64) -> &mut Self {
+        let slice = &f64::to_le_bytes(value);
+        impl_put_bytes!(self, slice);
+        self
+    }
+
+    /// Writes eight flags packed into a single byte, incrementing the position by `1`. Flags are ordered most-significant
+    /// bit first, so index `0` sets the bit `0x80`.
+    pub fn put_flags8(&mut self, flags: [bool; 8]) -> &mut Self {
+        let byte = flags
+            .iter()
+            .enumerate()
+            .fold(0u8, |byte, (i, flag)| if *flag { byte | (0x80 >> i) } else { byte });
+        self.put_u8(byte)
+    }
+
+    /// Writes a slice of signed bytes into the buffer, incrementing the position by `values.len()`.
+    pub fn put_i8_slice(&mut self, values: &[i8]) -> &mut Self {
+        let bytes: &[u8] = &values.iter().map(|value| *value as u8).collect::<Vec<_>>();
+        impl_put_bytes!(self, bytes);
+        self
+    }
+
+    /// Writes a slice of single-precision floats into the buffer in big-endian order, incrementing the position by
+    /// `values.len() * 4`.
+    pub fn put_f32_slice(&mut self, values: &[f32]) -> &mut Self {
+        let bytes: &[u8] = &values.iter().flat_map(|value| value.to_be_bytes()).collect::<Vec<_>>();
         impl_put_bytes!(self, bytes);
-        self.put_u8(0);
+        self
+    }
+
+    /// Writes a slice of double-precision floats into the buffer in big-endian order, incrementing the position by
+    /// `values.len() * 8`.
+    pub fn put_f64_slice(&mut self, values: &[f64]) -> &mut Self {
+        let bytes: &[u8] = &values.iter().flat_map(|value| value.to_be_bytes()).collect::<Vec<_>>();
+        impl_put_bytes!(self, bytes);
+        self
+    }
+
+    /// Writes a null-terminated string value into the buffer, incremeneting the position by `value.len() + 1`.
+    pub fn put_str<S: AsRef<str>>(&mut self, value: S) -> &mut Self {
+        self.write_spanned(|buf| {
+            let bytes: &[u8] = value.as_ref().as_bytes();
+            impl_put_bytes!(buf, bytes);
+            buf.put_u8(0)
+        })
+    }
+
+    /// Writes each of `items` as a null-terminated string, back to back, to be read by
+    /// [get_str_list](Buffered::get_str_list).
+    pub fn put_str_list<S: AsRef<str>>(&mut self, items: &[S]) -> &mut Self {
+        self.write_spanned(|buf| {
+            for item in items {
+                buf.put_str(item);
+            }
+            buf
+        })
+    }
+
+    /// Writes each of `items` as a null-terminated string followed by an extra null marking the end of the list, to be
+    /// read by [get_str_list_terminated](Buffered::get_str_list_terminated). An empty item would end the list early, so
+    /// use [put_str_list](Buffered::put_str_list) where items may be empty.
+    pub fn put_str_list_terminated<S: AsRef<str>>(&mut self, items: &[S]) -> &mut Self {
+        self.write_spanned(|buf| buf.put_str_list(items).put_u8(0))
+    }
+
+    /// Writes a null-terminated string value into the buffer as [put_str](Buffered::put_str) does, returning the byte range
+    /// it occupies including the terminator. This lets callers record where a field lives for later patching.
+    pub fn put_str_measured(&mut self, value: &str) -> Range<usize> {
+        let start = self.pos;
+        self.put_str(value);
+        start..self.pos
+    }
+
+    /// Writes an unsigned integer into the buffer using LEB128, incrementing the position by the width of the encoding.
+    pub fn put_var_u32(&mut self, mut value: u32) -> &mut Self {
+        self.write_spanned(|buf| {
+            while value >= 0x80 {
+                buf.put_u8(value as u8 | 0x80);
+                value >>= 7;
+            }
+            buf.put_u8(value as u8)
+        })
+    }
+
+    /// Writes an unsigned integer into the buffer as a MIDI-style variable-length quantity, most significant 7-bit group
+    /// first, incrementing the position by the width of the encoding.
+    pub fn put_midi_vlq(&mut self, value: u32) -> &mut Self {
+        self.write_spanned(|buf| {
+            let mut groups = [0u8; 5];
+            let mut len = 0;
+            let mut remaining = value;
+            loop {
+                groups[len] = (remaining & 0x7F) as u8;
+                len += 1;
+                remaining >>= 7;
+                if remaining == 0 {
+                    break;
+                }
+            }
+
+            for i in (0..len).rev() {
+                let continuation = if i > 0 { 0x80 } else { 0 };
+                buf.put_u8(groups[i] | continuation);
+            }
+            buf
+        })
+    }
+
+    /// Writes a UTF-8 string prefixed by its LEB128-encoded byte length, incrementing the position by the width of the
+    /// prefix plus `value.len()`.
+    pub fn put_str_var<S: AsRef<str>>(&mut self, value: S) -> &mut Self {
+        self.write_spanned(|buf| {
+            let bytes: &[u8] = value.as_ref().as_bytes();
+            buf.put_var_u32(bytes.len() as u32);
+            impl_put_bytes!(buf, bytes);
+            buf
+        })
+    }
+
+    /// Writes an optional value as read by [get_optional](Buffered::get_optional): a LEB128-encoded `0` for `None`, or a
+    /// `1` followed by the value as written by `f`.
+    pub fn put_optional<T, F>(&mut self, value: Option<T>, f: F) -> &mut Self
+    where
+        F: FnOnce(&mut Self, T) -> &mut Self,
+    {
+        self.write_spanned(|buf| {
+            match value {
+                Some(value) => f(buf.put_var_u32(1), value),
+                None => buf.put_var_u32(0),
+            }
+        })
+    }
+
+    /// Writes the remaining bytes of `other`, from its cursor onwards, into the buffer and advances the cursor of `other`
+    /// to its end. Nothing is written if the cursor of `other` is already at or past its end. The position of this buffer
+    /// is incremented by the number of bytes written.
+    pub fn put_buffered(&mut self, other: &mut Buffered<Bytes>) -> &mut Self {
+        self.write_spanned(|buf| {
+            let tail: &[u8] = other.tail();
+            impl_put_bytes!(buf, tail);
+            other.pos = usize::max(other.pos, other.end());
+            buf
+        })
+    }
+
+    /// Empties the buffer while retaining its allocation, returning the cursor to the start and discarding any marks,
+    /// saved positions, logical end, allocation limit or [tap](Buffered::with_tap).
+    pub fn clear(&mut self) {
+        self.buffer.bytes.clear();
+        self.buffer.filled = 0;
+        self.pos = 0;
+        self.mark = None;
+        self.saved.clear();
+        self.limit = None;
+        self.alloc_limit = None;
+        self.last_span = None;
+        self.tap = None;
+    }
+
+    /// Writes `payload` as a frame: its big-endian `u32` length, the payload itself, then a CRC-32 checksum covering both
+    /// the length and the payload. The position is incremented by `payload.len() + 8`.
+    pub fn write_frame(&mut self, payload: &[u8]) -> &mut Self {
+        self.write_spanned(|buf| {
+            let start = buf.pos;
+            buf.put_u32(payload.len() as u32);
+            impl_put_bytes!(buf, payload);
+            let checksum = crc32(&buf.buffer.bytes[start..buf.pos]);
+            buf.put_u32(checksum)
+        })
+    }
+
+    /// Writes `payload` as a checksummed section: its big-endian `u32` length, the payload itself, then a CRC-32 checksum
+    /// covering only the payload. Unlike [write_frame](Buffered::write_frame), the checksum excludes the length, suiting
+    /// nested sections whose checksum is defined over their body. The position is incremented by `payload.len() + 8`.
+    pub fn write_checked_section_u32(&mut self, payload: &[u8]) -> &mut Self {
+        self.write_spanned(|buf| {
+            buf.put_u32(payload.len() as u32);
+            impl_put_bytes!(buf, payload);
+            buf.put_u32(crc32(payload))
+        })
+    }
+
+    /// Inserts `data` at the byte offset `at`, shifting everything after it to the right. The cursor is moved along with
+    /// the data if it sits at or past `at`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at` is beyond the end of the backing buffer.
+    pub fn insert(&mut self, at: usize, data: &[u8]) {
+        self.buffer.grow_to(self.buffer.bytes.len() + data.len());
+        self.buffer.bytes.splice(at..at, data.iter().copied());
+        self.buffer.filled = usize::max(self.buffer.filled, at) + data.len();
+        if self.pos >= at {
+            self.pos += data.len();
+        }
+    }
+
+    /// Removes the bytes within `range`, shifting everything after it to the left. A cursor past the range is moved back
+    /// along with the data, while one inside the range is clamped to its start.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds of the backing buffer.
+    pub fn remove(&mut self, range: Range<usize>) {
+        let Range { start, end } = range;
+        self.buffer.bytes.drain(start..end);
+        if self.buffer.filled > start {
+            self.buffer.filled -= usize::min(self.buffer.filled, end) - start;
+        }
+        if self.pos >= end {
+            self.pos -= end - start;
+        } else if self.pos > start {
+            self.pos = start;
+        }
+    }
+
+    /// Overwrites every byte from the cursor to the end of the backing buffer with `byte`, moving the cursor to the end.
+    /// This is useful for wiping the unused tail of a buffer or writing a test pattern.
+    pub fn fill_remaining(&mut self, byte: u8) {
+        let pos = self.pos;
+        let len = self.buffer.bytes.len();
+        if pos < len {
+            self.buffer.bytes[pos..].fill(byte);
+            self.buffer.filled = len;
+            self.pos = len;
+        }
+    }
+
+    /// Reserves a fixed-width LEB128 slot for the length of a section whose size is not yet known, incrementing the
+    /// position by `5`. Write the section, then hand the returned placeholder to [patch_var_len](Buffered::patch_var_len).
+    pub fn reserve_var_len(&mut self) -> VarLenPlaceholder {
+        let pos = self.pos;
+        let slot: &[u8] = &[0x80, 0x80, 0x80, 0x80, 0x00];
+        impl_put_bytes!(self, slot);
+        VarLenPlaceholder { pos }
+    }
+
+    /// Fills a slot reserved by [reserve_var_len](Buffered::reserve_var_len) with the number of bytes written between the
+    /// end of the slot and the cursor. The length is padded with continuation bits to the full width of the slot, which
+    /// [get_var_u32](Buffered::get_var_u32) decodes as normal. Returns an error if the length overflows a `u32`, or if the
+    /// slot no longer lies within the buffer, as when the placeholder came from another buffer or this one was cleared.
+    pub fn patch_var_len(&mut self, placeholder: VarLenPlaceholder) -> Result<()> {
+        let start = placeholder.pos + VAR_LEN_WIDTH;
+        if start > self.buffer.filled {
+            return Err(BufferError::SlotOutOfBounds {
+                pos: placeholder.pos,
+                len: self.buffer.filled,
+            }
+            .into_error());
+        }
+        let len = u32::try_from(self.pos.saturating_sub(start)).map_err(|_| BufferError::Overflow)?;
+
+        for (i, byte) in self.buffer.bytes[placeholder.pos..start].iter_mut().enumerate() {
+            let continuation = if i + 1 < VAR_LEN_WIDTH { 0x80 } else { 0 };
+            *byte = (len >> (7 * i)) as u8 & 0x7F | continuation;
+        }
+        Ok(())
     }
 }
 
 impl ToSlice for Bytes {
-    fn slice(&self, range: std::ops::Range<usize>) -> Option<&[u8]> {
+    fn slice(&self, range: core::ops::Range<usize>) -> Option<&[u8]> {
         self.get(range.start..range.end)
     }
 
-    fn slice_to(&self, range: std::ops::RangeTo<usize>) -> Option<&[u8]> {
+    fn slice_to(&self, range: core::ops::RangeTo<usize>) -> Option<&[u8]> {
         self.get(..range.end)
     }
 
@@ -203,30 +1144,101 @@ impl ToSlice for Bytes {
         1
     }
 
+    /// Returns the length of the real data, excluding any padding left behind by growth, so that reads stop where the
+    /// written data ends.
+    fn len(&self) -> usize {
+        self.filled
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        self.grow_to(self.bytes.len().saturating_add(additional));
+    }
+}
+
+impl ToSlice for BytesRef<'_> {
+    fn slice(&self, range: core::ops::Range<usize>) -> Option<&[u8]> {
+        self.bytes.get(range)
+    }
+
+    fn slice_to(&self, range: core::ops::RangeTo<usize>) -> Option<&[u8]> {
+        self.bytes.get(range)
+    }
+
+    fn item_size_hint() -> usize {
+        1
+    }
+
     fn len(&self) -> usize {
         self.bytes.len()
     }
 }
 
+impl Deref for BytesRef<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        self.bytes
+    }
+}
+
+impl<'a> From<&'a [u8]> for BytesRef<'a> {
+    fn from(value: &'a [u8]) -> Self {
+        BytesRef::new(value)
+    }
+}
+
+/// Wipes the backing memory, including any spare capacity, leaving an empty buffer. Wrap the buffer in
+/// [Zeroizing](zeroize::Zeroizing) to have this happen automatically when it is dropped. With this feature enabled, the
+/// allocations left behind as the buffer grows are wiped before being freed too.
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for Bytes {
+    fn zeroize(&mut self) {
+        self.bytes.zeroize();
+        self.filled = 0;
+    }
+}
+
+/// Dereferences to the real data only, excluding any padding left behind by growth, in agreement with
+/// [len](ToSlice::len).
 impl Deref for Bytes {
     type Target = [u8];
 
     fn deref(&self) -> &Self::Target {
-        self.bytes.deref()
+        &self.bytes[..self.filled]
     }
 }
 
 impl From<Vec<u8>> for Bytes {
     fn from(value: Vec<u8>) -> Self {
-        Bytes { bytes: value }
+        Bytes::new(value)
+    }
+}
+
+impl From<Vec<u8>> for Buffered<Bytes> {
+    fn from(value: Vec<u8>) -> Self {
+        Buffered::using(Bytes::new(value))
+    }
+}
+
+impl From<Buffered<Bytes>> for Vec<u8> {
+    fn from(value: Buffered<Bytes>) -> Self {
+        value.finalize().bytes
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<io::Cursor<Vec<u8>>> for Buffered<Bytes> {
+    fn from(value: io::Cursor<Vec<u8>>) -> Self {
+        let pos = value.position() as usize;
+        let mut buffered = Buffered::using(Bytes::new(value.into_inner()));
+        buffered.set_position(pos);
+        buffered
     }
 }
 
 impl From<&[u8]> for Bytes {
     fn from(value: &[u8]) -> Self {
-        Bytes {
-            bytes: value.to_vec(),
-        }
+        Bytes::new(value.to_vec())
     }
 }
 
@@ -273,6 +1285,37 @@ impl Index<RangeFrom<usize>> for Bytes {
     }
 }
 
+/// Computes the number of bytes a value occupies once written, allowing exact capacity to be reserved up front.
+pub mod encoded_len {
+    /// Returns the width of `value` written by [put_var_u32](crate::Buffered::put_var_u32), from `1` to `5` bytes.
+    pub fn var_u32_len(value: u32) -> usize {
+        let bits = 32 - (value | 1).leading_zeros() as usize;
+        (bits + 6) / 7
+    }
+
+    /// Returns the width of `value` written by [put_midi_vlq](crate::Buffered::put_midi_vlq). This matches LEB128, as
+    /// both carry seven bits per byte.
+    pub fn midi_vlq_len(value: u32) -> usize {
+        var_u32_len(value)
+    }
+
+    /// Returns the width of `value` written by [put_str](crate::Buffered::put_str), including its null terminator.
+    pub fn str_len(value: &str) -> usize {
+        value.len() + 1
+    }
+
+    /// Returns the width of `value` written by [put_str_var](crate::Buffered::put_str_var), including its length prefix.
+    pub fn str_var_len(value: &str) -> usize {
+        var_u32_len(value.len() as u32) + value.len()
+    }
+
+    /// Returns the width of a frame written by [write_frame](crate::Buffered::write_frame) around a payload of
+    /// `payload_len` bytes.
+    pub fn frame_len(payload_len: usize) -> usize {
+        payload_len + 8
+    }
+}
+
 /// Composites used within buffer operations.
 pub(crate) mod composite {
 
@@ -281,6 +1324,29 @@ pub(crate) mod composite {
         (((buf[0] as u32) << 16) + ((buf[1] as u32) << 8) + (buf[2] as u32 & 255)) as usize
     }
 
+    /// A helper function reading a signed little-endian 24-bit value from the byte slice, sign-extending from the final byte.
+    pub(crate) fn read_i24_le(buf: &[u8]) -> i32 {
+        let value = (buf[0] as u32) | ((buf[1] as u32) << 8) | ((buf[2] as u32) << 16);
+        ((value << 8) as i32) >> 8
+    }
+
+    /// A helper function writing a signed 24-bit value into a fixed-length byte slice in little-endian order.
+    pub(crate) fn write_i24_le(value: i32) -> [u8; 3] {
+        [value as u8, (value >> 8) as u8, (value >> 16) as u8]
+    }
+
+    /// A helper function computing the CRC-32 (IEEE 802.3) checksum of the byte slice.
+    pub(crate) fn crc32(buf: &[u8]) -> u32 {
+        let mut crc = !0u32;
+        for byte in buf {
+            crc ^= *byte as u32;
+            for _ in 0..8 {
+                crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+            }
+        }
+        !crc
+    }
+
     /// A helper function writing a 24-bit value into a fixed-length byte slice.
     pub(crate) fn write_u24(value: u32) -> [u8; 3] {
         let mut bytes = [0u8; 3];
@@ -290,3 +1356,105 @@ pub(crate) mod composite {
         bytes
     }
 }
+
+/// Input traits allowing a [BytesRef] to be fed directly into [nom](::nom) parser combinators. Each implementation
+/// defers to the one nom provides for byte slices, while marking the view as [UnspecializedInput] picks up nom's generic
+/// `Compare` and `InputTakeAtPosition` implementations.
+#[cfg(feature = "nom")]
+mod nom_input {
+    use super::BytesRef;
+    use nom::{
+        AsBytes, FindSubstring, FindToken, InputIter, InputLength, InputTake, Needed, Offset, Slice, UnspecializedInput,
+    };
+    use core::iter::{Copied, Enumerate};
+    use core::ops::{Range, RangeFrom, RangeFull, RangeTo};
+    use core::slice::Iter;
+
+    impl InputLength for BytesRef<'_> {
+        fn input_len(&self) -> usize {
+            self.bytes.len()
+        }
+    }
+
+    impl<'a> InputIter for BytesRef<'a> {
+        type Item = u8;
+        type Iter = Enumerate<Self::IterElem>;
+        type IterElem = Copied<Iter<'a, u8>>;
+
+        fn iter_indices(&self) -> Self::Iter {
+            self.bytes.iter_indices()
+        }
+
+        fn iter_elements(&self) -> Self::IterElem {
+            self.bytes.iter_elements()
+        }
+
+        fn position<P>(&self, predicate: P) -> Option<usize>
+        where
+            P: Fn(Self::Item) -> bool,
+        {
+            self.bytes.iter().position(|byte| predicate(*byte))
+        }
+
+        fn slice_index(&self, count: usize) -> Result<usize, Needed> {
+            self.bytes.slice_index(count)
+        }
+    }
+
+    impl InputTake for BytesRef<'_> {
+        fn take(&self, count: usize) -> Self {
+            BytesRef::new(&self.bytes[..count])
+        }
+
+        fn take_split(&self, count: usize) -> (Self, Self) {
+            let (prefix, suffix) = self.bytes.split_at(count);
+            (BytesRef::new(suffix), BytesRef::new(prefix))
+        }
+    }
+
+    impl UnspecializedInput for BytesRef<'_> {}
+
+    impl<'a, T> FindSubstring<T> for BytesRef<'a>
+    where
+        &'a [u8]: FindSubstring<T>,
+    {
+        fn find_substring(&self, substr: T) -> Option<usize> {
+            self.bytes.find_substring(substr)
+        }
+    }
+
+    impl<'a, T> FindToken<T> for BytesRef<'a>
+    where
+        &'a [u8]: FindToken<T>,
+    {
+        fn find_token(&self, token: T) -> bool {
+            self.bytes.find_token(token)
+        }
+    }
+
+    impl Offset for BytesRef<'_> {
+        fn offset(&self, second: &Self) -> usize {
+            self.bytes.offset(second.bytes)
+        }
+    }
+
+    impl AsBytes for BytesRef<'_> {
+        fn as_bytes(&self) -> &[u8] {
+            self.bytes
+        }
+    }
+
+    macro_rules! impl_slice {
+        ($($range:ty),*) => {
+            $(
+                impl Slice<$range> for BytesRef<'_> {
+                    fn slice(&self, range: $range) -> Self {
+                        BytesRef::new(&self.bytes[range])
+                    }
+                }
+            )*
+        };
+    }
+
+    impl_slice!(Range<usize>, RangeTo<usize>, RangeFrom<usize>, RangeFull);
+}
@@ -0,0 +1,102 @@
+use crate::{
+    bytes::Bytes,
+    error::{buffer_error, BufferError, Error},
+    Buffered, Result,
+};
+
+/// A type which can be read from a byte buffer in a single step. Implemented for the primitive integers and for tuples of
+/// decodable types, allowing fixed headers of mixed types to be read with [get_tuple](Buffered::get_tuple).
+pub trait Decode: Sized {
+    /// Reads a value from the buffer, incrementing its position by the width of the value if successful. A failed decode
+    /// may leave the position partway through the value; read through [get_tuple](Buffered::get_tuple) to have it
+    /// restored.
+    fn decode(buf: &mut Buffered<Bytes>) -> Result<Self>;
+}
+
+macro_rules! impl_decode {
+    ($($ty:ty => $get:ident),* $(,)?) => {
+        $(
+            impl Decode for $ty {
+                fn decode(buf: &mut Buffered<Bytes>) -> Result<Self> {
+                    buf.$get()
+                }
+            }
+        )*
+    };
+}
+
+impl_decode! {
+    u8 => get_u8,
+    i8 => get_i8,
+    u16 => get_u16,
+    i16 => get_i16,
+    u32 => get_u32,
+    i32 => get_i32,
+    u64 => get_u64,
+    i64 => get_i64,
+}
+
+macro_rules! impl_decode_tuple {
+    ($($name:ident),+) => {
+        impl<$($name: Decode),+> Decode for ($($name,)+) {
+            fn decode(buf: &mut Buffered<Bytes>) -> Result<Self> {
+                Ok(($($name::decode(buf)?,)+))
+            }
+        }
+    };
+}
+
+impl_decode_tuple!(A);
+impl_decode_tuple!(A, B);
+impl_decode_tuple!(A, B, C);
+impl_decode_tuple!(A, B, C, D);
+impl_decode_tuple!(A, B, C, D, E);
+impl_decode_tuple!(A, B, C, D, E, F);
+impl_decode_tuple!(A, B, C, D, E, F, G);
+impl_decode_tuple!(A, B, C, D, E, F, G, H);
+
+/// The outcome of a decode over a buffer which may not yet hold a complete value, such as one filled by partial network
+/// reads. Unlike a plain [Result], running short of bytes is not treated as fatal: the caller is told how many more bytes
+/// are needed and may retry once they arrive.
+#[derive(Debug)]
+pub enum DecodeResult<T> {
+    /// The value was decoded in full.
+    Complete(T),
+    /// The buffer ends before the value does. `needed` is the number of additional bytes required to continue.
+    Incomplete { needed: usize },
+    /// Decoding failed for a reason more bytes will not fix.
+    Error(Error),
+}
+
+impl<T> DecodeResult<T> {
+    /// Returns `true` if decoding stopped for lack of bytes.
+    pub fn is_incomplete(&self) -> bool {
+        matches!(self, DecodeResult::Incomplete { .. })
+    }
+
+    /// Converts into a [Result], yielding `None` for incomplete input.
+    pub fn into_result(self) -> Result<Option<T>> {
+        match self {
+            DecodeResult::Complete(value) => Ok(Some(value)),
+            DecodeResult::Incomplete { .. } => Ok(None),
+            DecodeResult::Error(err) => Err(err),
+        }
+    }
+}
+
+/// Treats every [Eof](BufferError::Eof) as [Incomplete](DecodeResult::Incomplete). The conversion cannot see a logical end
+/// set with [limit](Buffered::limit), past which more input will never arrive; prefer the `try_get_*` methods of
+/// [Buffered], which account for it.
+impl<T> From<Result<T>> for DecodeResult<T> {
+    fn from(result: Result<T>) -> Self {
+        match result {
+            Ok(value) => DecodeResult::Complete(value),
+            Err(err) => match buffer_error(&err) {
+                Some(BufferError::Eof { needed, available }) => DecodeResult::Incomplete {
+                    needed: needed.saturating_sub(*available),
+                },
+                _ => DecodeResult::Error(err),
+            },
+        }
+    }
+}
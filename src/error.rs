@@ -0,0 +1,133 @@
+use core::fmt;
+#[cfg(feature = "std")]
+use std::{error, io};
+
+/// The error type returned by buffer operations. With the `std` feature this is the standard library's [io::Error],
+/// keeping the crate interoperable with IO code; without it, [BufferError] is used directly.
+#[cfg(feature = "std")]
+pub type Error = io::Error;
+
+/// The error type returned by buffer operations. With the `std` feature this is the standard library's `io::Error`,
+/// keeping the crate interoperable with IO code; without it, [BufferError] is used directly.
+#[cfg(not(feature = "std"))]
+pub type Error = BufferError;
+
+/// Describes why a buffer operation failed. With the `std` feature every variant converts into an `io::Error` carrying
+/// the matching `ErrorKind`, so methods keep returning the crate [Result](crate::Result) while callers that need the
+/// precise failure can recover it with `BufferError::from_io`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BufferError {
+    /// Fewer bytes remain than the operation required.
+    Eof { needed: usize, available: usize },
+    /// The bytes read were not valid UTF-8. `offset` is the position of the first invalid byte within the buffer.
+    InvalidUtf8 { offset: usize },
+    /// A decoded value does not fit within its target type.
+    Overflow,
+    /// A decoded length exceeds the buffer's allocation limit.
+    LimitExceeded { len: usize, limit: usize },
+    /// Unconsumed bytes remain after a parse was expected to reach the end of the buffer.
+    TrailingBytes { remaining: usize },
+    /// A value passed to a write does not fit within the width it is encoded as.
+    OutOfRange { value: u64, max: u64 },
+    /// A decoded discriminant does not name any of the variants it selects between.
+    InvalidDiscriminant { value: u32 },
+    /// A parser advanced further than the boundary it was given.
+    BoundaryExceeded { consumed: usize, max: usize },
+    /// A checksum read from the buffer does not match the one computed over the data it covers.
+    ChecksumMismatch { expected: u32, actual: u32 },
+    /// A reserved slot starting at `pos` does not fit within the `len` bytes of data in the buffer.
+    SlotOutOfBounds { pos: usize, len: usize },
+    /// The cursor was asked to move back further than its current position.
+    Underflow { requested: usize, pos: usize },
+    /// A reset was requested without a mark having been set.
+    NoMark,
+    /// A saved position was popped while none were saved.
+    NoSavedPosition,
+}
+
+/// Returns the [BufferError] behind a crate [Error], if the error originated from a buffer operation.
+pub(crate) fn buffer_error(err: &Error) -> Option<&BufferError> {
+    #[cfg(feature = "std")]
+    return BufferError::from_io(err);
+    #[cfg(not(feature = "std"))]
+    return Some(err);
+}
+
+impl BufferError {
+    /// Converts into the crate [Error]. Without the `std` feature the two are the same type, so the conversion is kept
+    /// here rather than leaving an identity `.into()` at every call site.
+    #[cfg_attr(not(feature = "std"), allow(clippy::useless_conversion))]
+    pub(crate) fn into_error(self) -> Error {
+        self.into()
+    }
+}
+
+#[cfg(feature = "std")]
+impl BufferError {
+    /// Returns the [BufferError] wrapped by an [io::Error], if the error originated from a buffer operation.
+    pub fn from_io(err: &io::Error) -> Option<&BufferError> {
+        err.get_ref().and_then(|inner| inner.downcast_ref())
+    }
+
+    /// Returns the [ErrorKind](io::ErrorKind) used when converting into an [io::Error].
+    pub fn kind(&self) -> io::ErrorKind {
+        match self {
+            BufferError::Eof { .. } => io::ErrorKind::UnexpectedEof,
+            BufferError::InvalidUtf8 { .. }
+            | BufferError::Overflow
+            | BufferError::LimitExceeded { .. }
+            | BufferError::TrailingBytes { .. }
+            | BufferError::BoundaryExceeded { .. }
+            | BufferError::InvalidDiscriminant { .. }
+            | BufferError::ChecksumMismatch { .. } => io::ErrorKind::InvalidData,
+            BufferError::OutOfRange { .. }
+            | BufferError::SlotOutOfBounds { .. }
+            | BufferError::Underflow { .. }
+            | BufferError::NoMark
+            | BufferError::NoSavedPosition => io::ErrorKind::InvalidInput,
+        }
+    }
+}
+
+impl fmt::Display for BufferError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BufferError::Eof { needed, available } => {
+                write!(f, "unexpected end of buffer: needed {} bytes but {} remain", needed, available)
+            }
+            BufferError::InvalidUtf8 { offset } => write!(f, "invalid utf-8 at offset {}", offset),
+            BufferError::Overflow => f.write_str("decoded value overflows its type"),
+            BufferError::LimitExceeded { len, limit } => {
+                write!(f, "length {} exceeds allocation limit of {}", len, limit)
+            }
+            BufferError::TrailingBytes { remaining } => write!(f, "{} trailing bytes remain unconsumed", remaining),
+            BufferError::InvalidDiscriminant { value } => write!(f, "invalid discriminant {}", value),
+            BufferError::BoundaryExceeded { consumed, max } => {
+                write!(f, "parser consumed {} bytes but was limited to {}", consumed, max)
+            }
+            BufferError::OutOfRange { value, max } => write!(f, "value {} exceeds maximum of {}", value, max),
+            BufferError::ChecksumMismatch { expected, actual } => {
+                write!(f, "checksum mismatch: expected {:#010x} but computed {:#010x}", expected, actual)
+            }
+            BufferError::SlotOutOfBounds { pos, len } => {
+                write!(f, "reserved slot at {} lies outside the {} bytes of data", pos, len)
+            }
+            BufferError::Underflow { requested, pos } => {
+                write!(f, "cannot move back {} bytes from position {}", requested, pos)
+            }
+            BufferError::NoMark => f.write_str("no mark set"),
+            BufferError::NoSavedPosition => f.write_str("no saved position"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl error::Error for BufferError {}
+
+#[cfg(feature = "std")]
+impl From<BufferError> for io::Error {
+    fn from(err: BufferError) -> Self {
+        io::Error::new(err.kind(), err)
+    }
+}
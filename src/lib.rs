@@ -1,15 +1,29 @@
-use std::{
-    io,
-    ops::{Deref, DerefMut, Range},
-};
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::{Deref, DerefMut, Range};
+#[cfg(feature = "std")]
+use std::io;
+
+use error::BufferError;
 
 pub mod bytes;
+pub mod decode;
+pub mod error;
+#[cfg(feature = "std")]
+pub mod pool;
 
-/// Result type which is simply an alias for the standard library's IO [Result](io::Result).
-pub type Result<T> = io::Result<T>;
+/// Result type which is simply an alias for the standard library's IO `Result` when the `std` feature is
+/// enabled, or a result carrying a [BufferError] otherwise.
+pub type Result<T> = core::result::Result<T, error::Error>;
 
 /// A function that simply returns an [ErrorKind](io::ErrorKind) of [UnexpectedEof](io::ErrorKind::UnexpectedEof). This serves as a helper
 /// function to act as a shorthand form for returning EOF errors within a fallible context.
+#[cfg(feature = "std")]
 pub fn eof<T>() -> io::Result<T> {
     Err(io::ErrorKind::UnexpectedEof.into())
 }
@@ -21,7 +35,7 @@ pub trait ToSlice: Sized {
     fn slice(&self, range: Range<usize>) -> Option<&[u8]>;
 
     /// Creates a slice containing all elements up until the range specified. This is equivalent to the interval notation `[0..end).`
-    fn slice_to(&self, range: std::ops::RangeTo<usize>) -> Option<&[u8]>;
+    fn slice_to(&self, range: core::ops::RangeTo<usize>) -> Option<&[u8]>;
 
     /// Used to optimistically assume sizes of a slice by using the size of a single item.
     fn item_size_hint() -> usize {
@@ -30,12 +44,34 @@ pub trait ToSlice: Sized {
 
     /// Returns the count of items within the container.
     fn len(&self) -> usize;
+
+    /// Reserves capacity for at least `additional` more bytes. The default implementation does nothing, which suits
+    /// containers that cannot grow.
+    fn reserve(&mut self, _additional: usize) {}
+
+    /// Returns `true` if the container holds no items.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
 }
 
-#[derive(Debug, Clone)]
+/// A callback receiving the bytes consumed by each read, installed with [with_tap](Buffered::with_tap).
+type Tap = Box<dyn FnMut(&[u8]) + Send + Sync>;
+
+/// A cursor over a container of bytes, reading and writing values at the current position.
+///
+/// Reads through the `get_*` and `read_frame*` methods of `Buffered<Bytes>` never partially consume their input: when
+/// one returns an error, whether for lack of bytes or malformed data, the cursor is left exactly where it was before the
+/// call, so the read may be retried once more bytes arrive or another parse attempted in its place.
 pub struct Buffered<T: ToSlice> {
     buffer: T,
     pos: usize,
+    mark: Option<usize>,
+    saved: Vec<usize>,
+    alloc_limit: Option<usize>,
+    limit: Option<usize>,
+    last_span: Option<Range<usize>>,
+    tap: Option<Tap>,
 }
 
 impl<T> Buffered<T>
@@ -44,17 +80,40 @@ where
 {
     /// Constructs a new buffer, using the provided vector as the initial contents and cursor position starting at 0.
     pub fn using(container: T) -> Self {
-        Self {
-            pos: 0,
-            buffer: container,
-        }
+        Self::from_container(container)
     }
 
-    /// Constructs a new buffer whose contents are initialized using the designated [Default](std::default::Default) implementation of type `T`.
+    /// Constructs a new buffer whose contents are initialized using the designated [Default] implementation of type `T`.
     pub fn new() -> Self {
+        Self::using(T::default())
+    }
+
+    /// Constructs a new, empty buffer with room reserved for `count` items, sized using [item_size_hint](ToSlice::item_size_hint).
+    /// A hint of `0` means nothing is reserved.
+    pub fn with_item_capacity(count: usize) -> Self {
+        let mut buffered = Self::new();
+        buffered.buffer.reserve(count.saturating_mul(T::item_size_hint()));
+        buffered
+    }
+}
+
+impl<T> Buffered<T>
+where
+    T: ToSlice,
+{
+    /// Constructs a new buffer around the provided container with the cursor position starting at 0. Unlike
+    /// [using](Buffered::using), this does not require `T` to implement [Default], so borrowed containers such as
+    /// [BytesRef](bytes::BytesRef) may be used.
+    pub fn from_container(container: T) -> Self {
         Self {
-            buffer: T::default(),
-            pos: 0
+            pos: 0,
+            buffer: container,
+            mark: None,
+            saved: Vec::new(),
+            alloc_limit: None,
+            limit: None,
+            last_span: None,
+            tap: None,
         }
     }
 
@@ -68,6 +127,11 @@ where
         &mut self.buffer
     }
 
+    /// Consumes the buffer, returning the underlying [ToSlice] implementor. The cursor position is discarded.
+    pub fn into_inner(self) -> T {
+        self.buffer
+    }
+
     /// Returns the raw position of the cursor within the buffer.
     pub fn pos(&self) -> usize {
         self.pos
@@ -78,24 +142,252 @@ where
         self.pos / T::item_size_hint()
     }
 
+    /// Returns the index of the cursor within the buffer, or `None` if the container reports an
+    /// [item_size_hint](ToSlice::item_size_hint) of `0`, where [index](Buffered::index) would panic.
+    pub fn try_index(&self) -> Option<usize> {
+        self.pos.checked_div(T::item_size_hint())
+    }
+
+    /// Returns `true` if the cursor sits on an item boundary, as sized by [item_size_hint](ToSlice::item_size_hint).
+    /// Containers with a hint of `0` are always considered aligned.
+    pub fn is_item_aligned(&self) -> bool {
+        match T::item_size_hint() {
+            0 => true,
+            hint => self.pos % hint == 0,
+        }
+    }
+
     /// Advances the cursor forward by an order of magnitude of `amount * size_hint`.
     pub fn advance_index(&mut self, amount: usize) {
         self.pos += amount * T::item_size_hint();
     }
 
+    /// Moves the cursor back by an order of magnitude of `amount * size_hint`. Returns an error, leaving the cursor
+    /// unchanged, if this would move it before the start of the buffer.
+    pub fn retreat_index(&mut self, amount: usize) -> Result<()> {
+        self.unread(amount.saturating_mul(T::item_size_hint()))
+    }
+
     /// Sets the cursor within the buffer to the specified index.
     pub fn set_position(&mut self, index: usize) {
         self.pos = index;
     }
 
+    /// Moves the cursor back by `n`, allowing previously read data to be read again. Returns an error if `n` exceeds the
+    /// current position.
+    pub fn unread(&mut self, n: usize) -> Result<()> {
+        if n > self.pos {
+            return Err(BufferError::Underflow {
+                requested: n,
+                pos: self.pos,
+            }
+            .into_error());
+        }
+        self.pos -= n;
+        Ok(())
+    }
+
     /// Returns the remaining space available within the buffer.
     pub fn remaining(&self) -> usize {
-        self.buffer.len() - self.pos
+        self.end().saturating_sub(self.pos)
+    }
+
+    /// Sets a logical end for the buffer at the absolute position `end`. Reads which would pass it fail as though the
+    /// buffer ended there, even when the backing container holds more, until [unlimit](Buffered::unlimit) is called.
+    pub fn limit(&mut self, end: usize) {
+        self.limit = Some(end);
+    }
+
+    /// Removes the logical end set by [limit](Buffered::limit), allowing reads up to the end of the backing container.
+    pub fn unlimit(&mut self) {
+        self.limit = None;
+    }
+
+    /// Returns the position at which reads stop: the logical end set by [limit](Buffered::limit), if any, otherwise the
+    /// end of the backing container.
+    pub(crate) fn end(&self) -> usize {
+        match self.limit {
+            Some(limit) => usize::min(limit, self.buffer.len()),
+            None => self.buffer.len(),
+        }
+    }
+
+    /// Returns the number of whole items remaining within the buffer, sized using [item_size_hint](ToSlice::item_size_hint).
+    /// Containers with a hint of `0` report the remaining bytes instead.
+    pub fn remaining_items(&self) -> usize {
+        match T::item_size_hint() {
+            0 => self.remaining(),
+            hint => self.remaining() / hint,
+        }
     }
 
     pub fn is_available(&self, amount: usize) -> bool {
         self.remaining() >= amount
     }
+
+    /// Asserts that the buffer has been consumed in its entirety, returning a [TrailingBytes](BufferError::TrailingBytes)
+    /// error if any bytes remain past the cursor.
+    pub fn finish(&self) -> Result<()> {
+        match self.remaining() {
+            0 => Ok(()),
+            remaining => Err(BufferError::TrailingBytes { remaining }.into_error()),
+        }
+    }
+
+    /// Bookmarks the current cursor position so that it may later be restored with [reset](Buffered::reset). Any previous
+    /// mark is replaced.
+    pub fn mark(&mut self) {
+        self.mark = Some(self.pos);
+    }
+
+    /// Restores the cursor to the position saved by the last call to [mark](Buffered::mark). The mark is kept, allowing
+    /// repeated resets. Returns an error if no mark is set.
+    pub fn reset(&mut self) -> Result<()> {
+        match self.mark {
+            Some(mark) => {
+                self.pos = mark;
+                Ok(())
+            }
+            None => Err(BufferError::NoMark.into_error()),
+        }
+    }
+
+    /// Removes the current mark, committing to the cursor position. Subsequent calls to [reset](Buffered::reset) will fail
+    /// until a new mark is set.
+    pub fn clear_mark(&mut self) {
+        self.mark = None;
+    }
+
+    /// Returns `true` if a mark is currently set.
+    pub fn has_mark(&self) -> bool {
+        self.mark.is_some()
+    }
+
+    /// Returns the byte range occupied by the value most recently read or written, such as through `get_u32` or
+    /// `put_str`, or `None` if nothing has been read or written yet. Failed reads leave the span unchanged.
+    pub fn last_span(&self) -> Option<Range<usize>> {
+        self.last_span.clone()
+    }
+
+    /// Saves the current cursor position onto a stack, allowing nested speculative parses to each restore their own
+    /// starting point with [pop_pos](Buffered::pop_pos) independently of the single [mark](Buffered::mark).
+    pub fn push_pos(&mut self) {
+        self.saved.push(self.pos);
+    }
+
+    /// Restores the cursor to the most recently saved position, removing it from the stack. Returns an error if no
+    /// positions are saved.
+    pub fn pop_pos(&mut self) -> Result<()> {
+        self.pos = self.saved.pop().ok_or(BufferError::NoSavedPosition)?;
+        Ok(())
+    }
+
+    /// Removes the most recently saved position without restoring it, committing to the current cursor position. Returns
+    /// an error if no positions are saved.
+    pub fn discard_pos(&mut self) -> Result<()> {
+        self.saved.pop().ok_or(BufferError::NoSavedPosition)?;
+        Ok(())
+    }
+
+    /// Sets the largest length, in bytes, that a length-prefixed read may allocate. Decoded lengths beyond this limit are
+    /// rejected before any allocation takes place.
+    pub fn set_alloc_limit(&mut self, limit: usize) {
+        self.alloc_limit = Some(limit);
+    }
+
+    /// Returns the allocation limit for length-prefixed reads, or `None` if no limit is set.
+    pub fn alloc_limit(&self) -> Option<usize> {
+        self.alloc_limit
+    }
+
+    /// Runs the element parser `f` exactly `count` times, collecting each decoded value. Decoding stops at the first
+    /// failing invocation, whose error is returned with the cursor left at the point of failure.
+    pub fn decode_each<R, F>(&mut self, count: usize, mut f: F) -> Result<Vec<R>>
+    where
+        F: FnMut(&mut Self) -> Result<R>,
+    {
+        let mut values = Vec::new();
+        for _ in 0..count {
+            values.push(f(self)?);
+        }
+        Ok(values)
+    }
+
+    /// Runs the parser `f`, checking that it advanced the cursor by no more than `max_bytes`. This catches sub-parsers
+    /// which over-read the message they were handed, such as when a length field disagrees with the body it describes.
+    /// If `f` fails or over-reads, an error is returned and the cursor is restored to where it was before `f` ran.
+    pub fn guard_within<R, F>(&mut self, max_bytes: usize, f: F) -> Result<R>
+    where
+        F: FnOnce(&mut Self) -> Result<R>,
+    {
+        let pos = self.pos;
+        let result = f(self).and_then(|value| {
+            let consumed = self.pos.saturating_sub(pos);
+            if consumed > max_bytes {
+                return Err(BufferError::BoundaryExceeded { consumed, max: max_bytes }.into_error());
+            }
+            Ok(value)
+        });
+
+        if result.is_err() {
+            self.pos = pos;
+        }
+        result
+    }
+
+    /// Ensures that `len` bytes fall within the allocation limit, returning a [LimitExceeded](BufferError::LimitExceeded)
+    /// error otherwise.
+    pub(crate) fn check_alloc(&self, len: usize) -> Result<()> {
+        match self.alloc_limit {
+            Some(limit) if len > limit => Err(BufferError::LimitExceeded { len, limit }.into_error()),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<T> Clone for Buffered<T>
+where
+    T: ToSlice + Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            buffer: self.buffer.clone(),
+            pos: self.pos,
+            mark: self.mark,
+            saved: self.saved.clone(),
+            alloc_limit: self.alloc_limit,
+            limit: self.limit,
+            last_span: self.last_span.clone(),
+            tap: None,
+        }
+    }
+}
+
+impl<T> fmt::Debug for Buffered<T>
+where
+    T: ToSlice + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Buffered")
+            .field("buffer", &self.buffer)
+            .field("pos", &self.pos)
+            .field("mark", &self.mark)
+            .field("saved", &self.saved)
+            .field("alloc_limit", &self.alloc_limit)
+            .field("limit", &self.limit)
+            .field("last_span", &self.last_span)
+            .field("tap", &self.tap.is_some())
+            .finish()
+    }
+}
+
+impl<T> Default for Buffered<T>
+where
+    T: ToSlice + Default,
+{
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<T> Deref for Buffered<T>
@@ -118,8 +410,10 @@ where
     }
 }
 
+#[cfg(feature = "std")]
 pub struct Logger;
 
+#[cfg(feature = "std")]
 impl log::Log for Logger {
     fn enabled(&self, _metadata: &log::Metadata) -> bool {
         true
@@ -134,11 +428,41 @@ impl log::Log for Logger {
 }
 
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
-    use std::env;
+    use crate::{Buffered, ToSlice, bytes::{Bytes, BytesRef}, decode::DecodeResult, error::BufferError, pool::BufferPool};
+
+    /// A container of big-endian 16-bit words, used to exercise item-level cursor arithmetic.
+    #[derive(Default)]
+    struct Words {
+        bytes: Vec<u8>,
+    }
+
+    impl Words {
+        fn new(words: &[u16]) -> Self {
+            Self {
+                bytes: words.iter().flat_map(|word| word.to_be_bytes()).collect(),
+            }
+        }
+    }
+
+    impl ToSlice for Words {
+        fn slice(&self, range: std::ops::Range<usize>) -> Option<&[u8]> {
+            self.bytes.get(range)
+        }
+
+        fn slice_to(&self, range: std::ops::RangeTo<usize>) -> Option<&[u8]> {
+            self.bytes.get(range)
+        }
+
+        fn item_size_hint() -> usize {
+            2
+        }
 
-    use crate::{Buffered, bytes::Bytes, Logger};
+        fn len(&self) -> usize {
+            self.bytes.len()
+        }
+    }
 
     #[test]
     pub fn single_read() {
@@ -154,6 +478,7 @@ mod tests {
     }
 
     #[test]
+    #[should_panic(expected = "eof")]
     pub fn throw_eof() {
         let mut bytes: Buffered<Bytes> = Buffered::using(vec![10u8].into());
         assert!(bytes.is_available(1));
@@ -161,4 +486,1173 @@ mod tests {
         assert!(bytes.remaining() == 0);
         bytes.get_u8().expect("eof");
     }
-}
\ No newline at end of file
+
+    #[test]
+    pub fn mark_and_reset() {
+        let mut bytes: Buffered<Bytes> = Buffered::using(vec![1u8, 2, 3].into());
+        bytes.get_u8().expect("read first entry");
+        bytes.mark();
+        assert!(bytes.has_mark());
+        bytes.get_u16().expect("read remaining entries");
+        bytes.reset().expect("reset to mark");
+        assert_eq!(bytes.pos(), 1);
+    }
+
+    #[test]
+    pub fn reset_after_clear_mark() {
+        let mut bytes: Buffered<Bytes> = Buffered::using(vec![1u8, 2, 3].into());
+        bytes.mark();
+        bytes.get_u8().expect("read first entry");
+        bytes.clear_mark();
+        assert!(!bytes.has_mark());
+        assert!(bytes.reset().is_err());
+        assert_eq!(bytes.pos(), 1);
+    }
+
+    #[test]
+    pub fn reserve_item_capacity() {
+        let bytes = Buffered::<Bytes>::with_item_capacity(100);
+        assert!(bytes.is_empty());
+        assert!(bytes.capacity() >= 100);
+    }
+
+    #[test]
+    pub fn var_str_round_trip() {
+        let long = "a".repeat(300);
+        let mut bytes = Buffered::<Bytes>::new();
+        bytes.put_str_var("hello");
+        bytes.put_str_var(&long);
+        assert_eq!(&bytes.bytes()[6..8], &[0xAC, 0x02]);
+
+        bytes.set_position(0);
+        assert_eq!(bytes.get_str_var().expect("read short string"), "hello");
+        assert_eq!(bytes.get_str_var().expect("read long string"), long);
+    }
+
+    #[test]
+    pub fn var_str_alloc_limit() {
+        let mut bytes = Buffered::<Bytes>::new();
+        bytes.put_str_var("a".repeat(200));
+        bytes.set_position(0);
+        bytes.set_alloc_limit(128);
+        assert!(bytes.get_str_var().is_err());
+        assert_eq!(bytes.pos(), 0);
+    }
+
+    #[test]
+    pub fn unread_and_reread() {
+        let mut bytes: Buffered<Bytes> = Buffered::using(vec![0xDEu8, 0xAD, 0xBE, 0xEF].into());
+        let first = bytes.get_u32().expect("read first u32");
+        bytes.unread(4).expect("unread u32");
+        assert_eq!(bytes.get_u32().expect("reread u32"), first);
+        assert!(bytes.unread(5).is_err());
+    }
+
+    #[test]
+    pub fn contents_of_read_buffer() {
+        let mut bytes: Buffered<Bytes> = Buffered::using(vec![1u8, 2, 3].into());
+        bytes.get_u8().expect("read first entry");
+        assert_eq!(bytes.contents(), &[1, 2, 3]);
+    }
+
+    #[test]
+    pub fn contents_of_written_buffer() {
+        let mut bytes = Buffered::<Bytes>::new();
+        bytes.put_u16(0x0102);
+        bytes.put_u8(3);
+        assert!(bytes.bytes().len() > 3);
+        assert_eq!(bytes.contents(), &[1, 2, 3]);
+
+        bytes.set_position(1);
+        bytes.put_u8(9);
+        assert_eq!(bytes.contents(), &[1, 9, 3]);
+    }
+
+    #[test]
+    pub fn insert_in_middle() {
+        let mut bytes: Buffered<Bytes> = Buffered::using(vec![1u8, 2, 3, 4].into());
+        bytes.set_position(2);
+        bytes.insert(2, &[8, 9]);
+        assert_eq!(bytes.contents(), &[1, 2, 8, 9, 3, 4]);
+        assert_eq!(bytes.pos(), 4);
+        assert_eq!(bytes.get_u8().expect("read shifted entry"), 3);
+
+        bytes.insert(5, &[7]);
+        assert_eq!(bytes.contents(), &[1, 2, 8, 9, 3, 7, 4]);
+        assert_eq!(bytes.pos(), 6);
+    }
+
+    #[test]
+    pub fn remove_range() {
+        let mut bytes: Buffered<Bytes> = Buffered::using(vec![1u8, 2, 3, 4, 5].into());
+        bytes.set_position(4);
+        bytes.remove(1..3);
+        assert_eq!(bytes.contents(), &[1, 4, 5]);
+        assert_eq!(bytes.pos(), 2);
+        assert_eq!(bytes.get_u8().expect("read shifted entry"), 5);
+
+        bytes.set_position(2);
+        bytes.remove(1..3);
+        assert_eq!(bytes.contents(), &[1]);
+        assert_eq!(bytes.pos(), 1);
+    }
+
+    #[test]
+    pub fn recover_inner() {
+        let mut bytes = Buffered::<Bytes>::new();
+        bytes.put_u8(1);
+        bytes.put_u16(0x0203);
+        let inner: Bytes = bytes.into_inner();
+        assert!(inner.starts_with(&[1, 2, 3]));
+    }
+
+    #[test]
+    pub fn flags_round_trip() {
+        let mut bytes: Buffered<Bytes> = Buffered::using(vec![0b1010_0001u8].into());
+        let flags = bytes.get_flags8().expect("read flags");
+        assert_eq!(flags, [true, false, true, false, false, false, false, true]);
+
+        let mut written = Buffered::<Bytes>::new();
+        written.put_flags8(flags);
+        assert_eq!(written.contents(), &[0b1010_0001]);
+    }
+
+    #[test]
+    pub fn decode_records() {
+        #[derive(Debug, PartialEq)]
+        struct Record {
+            id: u8,
+            value: u16,
+        }
+
+        let mut bytes: Buffered<Bytes> = Buffered::using(vec![1u8, 0, 10, 2, 0, 20, 3, 0, 30, 4].into());
+        let records = bytes
+            .decode_each(3, |buf| {
+                Ok(Record {
+                    id: buf.get_u8()?,
+                    value: buf.get_u16()?,
+                })
+            })
+            .expect("decode records");
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[2], Record { id: 3, value: 30 });
+        assert_eq!(bytes.pos(), 9);
+
+        bytes.set_position(3);
+        assert!(bytes.decode_each(4, |buf| buf.get_u16()).is_err());
+        assert_eq!(bytes.pos(), 9);
+    }
+
+    #[test]
+    pub fn peek_trailer() {
+        let bytes: Buffered<Bytes> = Buffered::using(vec![1u8, 2, 3, 4, 5, 6].into());
+        assert_eq!(bytes.peek_bytes_at(2, 4), Some(&[3u8, 4, 5, 6][..]));
+        assert_eq!(bytes.pos(), 0);
+        assert_eq!(bytes.peek_bytes_at(3, 4), None);
+        assert_eq!(bytes.peek_bytes_at(usize::MAX, 2), None);
+    }
+
+    #[test]
+    pub fn fill_tail() {
+        let mut bytes: Buffered<Bytes> = Buffered::using(vec![0u8; 6].into());
+        bytes.put_u16(0x0102);
+        bytes.fill_remaining(0xFF);
+        assert_eq!(bytes.contents(), &[1, 2, 0xFF, 0xFF, 0xFF, 0xFF]);
+        assert_eq!(bytes.remaining(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "zeroize")]
+    pub fn zeroize_backing_bytes() {
+        use zeroize::Zeroize;
+
+        let mut bytes: Buffered<Bytes> = Buffered::using(vec![0xAAu8; 16].into());
+        let capacity = bytes.capacity();
+        bytes.get_inner_mut().zeroize();
+        assert!(bytes.is_empty());
+        assert_eq!(bytes.capacity(), capacity);
+
+        let backing = unsafe { std::slice::from_raw_parts(bytes.as_ptr(), capacity) };
+        assert!(backing.iter().all(|byte| *byte == 0));
+    }
+
+    #[test]
+    pub fn error_variants() {
+        let kind = |err: std::io::Error| BufferError::from_io(&err).cloned().expect("buffer error");
+
+        let mut bytes: Buffered<Bytes> = Buffered::using(vec![1u8, 2].into());
+        let err = bytes.get_u32().expect_err("short read");
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+        assert_eq!(kind(err), BufferError::Eof { needed: 4, available: 2 });
+
+        let mut bytes: Buffered<Bytes> = Buffered::using(vec![b'o', b'k', 0xFF, 0].into());
+        let err = bytes.get_str().expect_err("invalid utf-8");
+        assert_eq!(kind(err), BufferError::InvalidUtf8 { offset: 2 });
+
+        let mut bytes: Buffered<Bytes> = Buffered::using(vec![0xFFu8, 0xFF, 0xFF, 0xFF, 0x1F].into());
+        let err = bytes.get_var_u32().expect_err("varint overflow");
+        assert_eq!(kind(err), BufferError::Overflow);
+
+        let mut bytes = Buffered::<Bytes>::new();
+        bytes.put_str_var("overlong");
+        bytes.set_position(0);
+        bytes.set_alloc_limit(4);
+        let err = bytes.get_str_var().expect_err("limit exceeded");
+        assert_eq!(kind(err), BufferError::LimitExceeded { len: 8, limit: 4 });
+    }
+
+    #[test]
+    pub fn finish_rejects_trailing_bytes() {
+        let mut bytes: Buffered<Bytes> = Buffered::using(vec![0u8, 1, 2].into());
+        bytes.get_u16().expect("read u16");
+        let err = bytes.finish().expect_err("trailing byte");
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(BufferError::from_io(&err), Some(&BufferError::TrailingBytes { remaining: 1 }));
+
+        bytes.get_u8().expect("read final byte");
+        bytes.finish().expect("fully consumed");
+    }
+
+    #[test]
+    pub fn read_table_entry() {
+        let bytes: Buffered<Bytes> = Buffered::using(vec![0xFFu8, 0xFF, 0, 0, 0, 10, 0, 0, 0, 20, 0, 0, 0, 30].into());
+        assert_eq!(bytes.read_u32_at_index(2, 2).expect("read third entry"), 30);
+        assert_eq!(bytes.pos(), 0);
+        assert!(bytes.read_u32_at_index(2, 3).is_err());
+        assert!(bytes.read_u32_at_index(2, usize::MAX).is_err());
+    }
+
+    #[test]
+    pub fn chained_writes() {
+        let mut bytes = Buffered::<Bytes>::new();
+        bytes.put_u8(1).put_u16(2).put_str("x").put_var_u32(300);
+        assert_eq!(bytes.contents(), &[1, 0, 2, b'x', 0, 0xAC, 0x02]);
+    }
+
+    #[test]
+    pub fn finalize_strips_padding() {
+        let mut bytes = Buffered::<Bytes>::new();
+        bytes.put_u16(0x0102).put_u8(3);
+        assert!(bytes.bytes().len() > 3);
+        let finalized = bytes.finalize();
+        assert_eq!(&*finalized, &[1, 2, 3]);
+    }
+
+    #[test]
+    pub fn borrowed_container() {
+        let data = [1u8, 2, 3, 4];
+        let mut bytes = Buffered::from_container(BytesRef::new(&data));
+        assert_eq!(bytes.remaining(), 4);
+        bytes.advance_index(3);
+        assert_eq!(bytes.index(), 3);
+        assert_eq!(bytes.slice(bytes.pos()..bytes.len()), Some(&[4u8][..]));
+    }
+
+    #[test]
+    pub fn raw_cstr_bytes() {
+        let mut bytes: Buffered<Bytes> = Buffered::using(vec![b'a', 0, 0xFF, 0xFE, 0, b'z'].into());
+        assert_eq!(bytes.get_str().expect("read utf-8 string"), "a");
+        assert_eq!(bytes.get_cstr_bytes().expect("read raw string"), vec![0xFF, 0xFE]);
+        assert_eq!(bytes.pos(), 5);
+        assert!(bytes.get_cstr_bytes().is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "nom")]
+    pub fn parse_with_nom() {
+        use nom::{
+            bytes::complete::{tag, take},
+            number::complete::{be_u16, be_u8},
+            sequence::tuple,
+            IResult, InputLength,
+        };
+
+        fn header(input: BytesRef<'_>) -> IResult<BytesRef<'_>, (u8, u16, BytesRef<'_>)> {
+            let (input, _) = tag(&b"HD"[..])(input)?;
+            tuple((be_u8, be_u16, take(2usize)))(input)
+        }
+
+        let mut bytes: Buffered<Bytes> = Buffered::using(vec![0xFFu8, b'H', b'D', 1, 0x01, 0x02, b'o', b'k', 9].into());
+        bytes.get_u8().expect("skip leading byte");
+
+        let input = bytes.remaining_ref();
+        let (rest, (version, len, name)) = header(input).expect("parse header");
+        assert_eq!((version, len, &*name), (1, 0x0102, &b"ok"[..]));
+
+        let consumed = input.input_len() - rest.input_len();
+        bytes.advance_index(consumed);
+        assert_eq!(bytes.get_u8().expect("read trailing byte"), 9);
+    }
+
+    #[test]
+    pub fn pipe_buffered_tail() {
+        let mut source: Buffered<Bytes> = Buffered::using(vec![1u8, 2, 3, 4, 5].into());
+        source.get_u16().expect("read head");
+
+        let mut sink = Buffered::<Bytes>::new();
+        sink.put_u8(0xAA).put_buffered(&mut source).put_u8(0xBB);
+        assert_eq!(sink.contents(), &[0xAA, 3, 4, 5, 0xBB]);
+        assert_eq!(sink.pos(), 5);
+        assert_eq!(source.remaining(), 0);
+    }
+
+    #[test]
+    pub fn seek_to_sync_marker() {
+        let mut bytes: Buffered<Bytes> = Buffered::using(vec![0x13u8, 0x37, 0x00, 0x7E, 0x42].into());
+        bytes.seek_to_byte(0x7E).expect("find marker");
+        assert_eq!(bytes.pos(), 3);
+        assert_eq!(bytes.get_u8().expect("read marker"), 0x7E);
+
+        assert!(bytes.seek_to_byte(0x7E).is_err());
+        assert_eq!(bytes.pos(), 4);
+    }
+
+    #[test]
+    pub fn take_digit_run() {
+        let mut bytes: Buffered<Bytes> = Buffered::using(b"1234ab".into());
+        assert_eq!(bytes.take_while(|byte| byte.is_ascii_digit()), b"1234");
+        assert_eq!(bytes.pos(), 4);
+        assert!(bytes.take_while(|byte| byte.is_ascii_digit()).is_empty());
+        assert_eq!(bytes.take_while(|byte| byte.is_ascii_alphabetic()), b"ab");
+        assert_eq!(bytes.remaining(), 0);
+    }
+
+    #[test]
+    pub fn cursor_round_trip() {
+        let mut cursor = std::io::Cursor::new(vec![1u8, 2, 3, 4]);
+        cursor.set_position(1);
+
+        let mut bytes: Buffered<Bytes> = cursor.into();
+        assert_eq!(bytes.pos(), 1);
+        assert_eq!(bytes.get_u8().expect("read from cursor position"), 2);
+        bytes.put_u16(0x0506);
+
+        let cursor = bytes.into_cursor();
+        assert_eq!(cursor.position(), 4);
+        assert_eq!(cursor.get_ref(), &vec![1, 2, 5, 6]);
+    }
+
+    #[test]
+    pub fn signed_byte_slice_round_trip() {
+        let samples = [0i8, -1, 127, -128, -42];
+        let mut bytes = Buffered::<Bytes>::new();
+        bytes.put_i8_slice(&samples);
+        assert_eq!(bytes.contents(), &[0x00, 0xFF, 0x7F, 0x80, 0xD6]);
+
+        bytes.set_position(0);
+        assert_eq!(bytes.get_i8_slice(5).expect("read samples"), samples);
+        bytes.set_position(1);
+        assert!(bytes.get_i8_slice(bytes.remaining() + 1).is_err());
+        assert_eq!(bytes.pos(), 1);
+    }
+
+    #[test]
+    pub fn remaining_words() {
+        let mut words = Buffered::using(Words::new(&[1, 2, 3, 4]));
+        assert_eq!(words.remaining(), 8);
+        assert_eq!(words.remaining_items(), 4);
+        words.advance_index(1);
+        assert_eq!(words.remaining_items(), 3);
+
+        let bytes: Buffered<Bytes> = Buffered::using(vec![0u8; 3].into());
+        assert_eq!(bytes.remaining_items(), bytes.remaining());
+    }
+
+    #[test]
+    pub fn float_slice_round_trip() {
+        let vertex = [1.5f32, -0.25, f32::NAN];
+        let mut bytes = Buffered::<Bytes>::new();
+        bytes.put_f32_slice(&vertex).put_f64_slice(&[std::f64::consts::PI]);
+        assert_eq!(&bytes.contents()[..4], &1.5f32.to_be_bytes());
+
+        bytes.set_position(0);
+        let read = bytes.get_f32_slice(3).expect("read vertex");
+        assert_eq!(&read[..2], &vertex[..2]);
+        assert_eq!(read[2].to_bits(), f32::NAN.to_bits());
+        assert_eq!(bytes.get_f64_slice(1).expect("read double"), [std::f64::consts::PI]);
+
+        bytes.set_position(0);
+        bytes.set_alloc_limit(8);
+        assert!(bytes.get_f32_slice(3).is_err());
+        assert_eq!(bytes.pos(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "bytemuck")]
+    pub fn reinterpret_as_words() {
+        let words = [1u32, 2, 3, u32::MAX];
+        let bytes: Buffered<Bytes> = Buffered::using(bytemuck::cast_slice::<u32, u8>(&words).into());
+        if bytes.as_ptr() as usize % std::mem::align_of::<u32>() == 0 {
+            assert_eq!(bytes.as_slice_of::<u32>(), Some(&words[..]));
+        } else {
+            assert_eq!(bytes.as_slice_of::<u32>(), None);
+        }
+
+        let uneven: Buffered<Bytes> = Buffered::using(vec![0u8; 15].into());
+        assert_eq!(uneven.as_slice_of::<u32>(), None);
+    }
+
+    #[test]
+    pub fn incomplete_decode() {
+        let mut bytes: Buffered<Bytes> = Buffered::using(vec![0u8, 1].into());
+        match bytes.try_get_u32() {
+            DecodeResult::Incomplete { needed } => assert_eq!(needed, 2),
+            other => panic!("expected incomplete, got {:?}", other),
+        }
+        assert_eq!(bytes.pos(), 0);
+
+        assert!(matches!(bytes.try_get_u16(), DecodeResult::Complete(1)));
+        assert_eq!(bytes.pos(), 2);
+        assert!(bytes.try_get_u8().is_incomplete());
+    }
+
+    #[test]
+    pub fn exact_read() {
+        let mut bytes: Buffered<Bytes> = Buffered::using(vec![1u8, 2, 3, 4].into());
+        bytes.get_u8().expect("read first entry");
+        assert_eq!(bytes.get_exact(3).expect("read exact remainder"), vec![2, 3, 4]);
+        assert_eq!(bytes.remaining(), 0);
+    }
+
+    #[test]
+    pub fn exact_read_one_short() {
+        let mut bytes: Buffered<Bytes> = Buffered::using(vec![1u8, 2, 3, 4].into());
+        bytes.get_u8().expect("read first entry");
+        let err = bytes.get_exact(4).expect_err("one byte short");
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+        assert_eq!(bytes.pos(), 1);
+    }
+
+    #[test]
+    pub fn item_alignment() {
+        let mut words = Buffered::using(Words::new(&[1, 2]));
+        assert!(words.is_item_aligned());
+
+        // Step over a single byte, as a byte-level read would.
+        words.set_position(words.pos() + 1);
+        assert!(!words.is_item_aligned());
+
+        words.set_position(2);
+        assert!(words.is_item_aligned());
+    }
+
+    #[test]
+    pub fn back_patched_var_len() {
+        let mut bytes = Buffered::<Bytes>::new();
+        let placeholder = bytes.reserve_var_len();
+        bytes.put_str("section").put_u32(0xCAFE_BABE);
+        bytes.patch_var_len(placeholder).expect("patch length");
+        bytes.put_u8(0xFF);
+
+        bytes.set_position(0);
+        assert_eq!(bytes.get_var_u32().expect("read length"), 12);
+        assert_eq!(bytes.pos(), 5);
+        assert_eq!(bytes.get_str().expect("read section"), "section");
+        assert_eq!(bytes.get_u32().expect("read section"), 0xCAFE_BABE);
+        assert_eq!(bytes.get_u8().expect("read trailer"), 0xFF);
+    }
+
+    #[test]
+    pub fn logical_limit() {
+        let mut bytes: Buffered<Bytes> = Buffered::using(vec![0u8; 12].into());
+        bytes.limit(8);
+        bytes.get_u64().expect("read up to limit");
+        assert_eq!(bytes.remaining(), 0);
+        let err = bytes.get_u8().expect_err("read past limit");
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+        assert_eq!(bytes.pos(), 8);
+
+        bytes.unlimit();
+        assert_eq!(bytes.remaining(), 4);
+        bytes.get_u8().expect("read past former limit");
+    }
+
+    #[test]
+    pub fn signed_i24_le() {
+        const MIN: i32 = -(1 << 23);
+        const MAX: i32 = (1 << 23) - 1;
+
+        let mut bytes = Buffered::<Bytes>::new();
+        bytes.put_i24_le(-1).put_i24_le(MIN).put_i24_le(MAX);
+        assert_eq!(bytes.contents(), &[0xFF, 0xFF, 0xFF, 0x00, 0x00, 0x80, 0xFF, 0xFF, 0x7F]);
+
+        bytes.set_position(0);
+        assert_eq!(bytes.get_i24_le().expect("read -1"), -1);
+        assert_eq!(bytes.get_i24_le().expect("read min"), MIN);
+        assert_eq!(bytes.get_i24_le().expect("read max"), MAX);
+    }
+
+    #[test]
+    pub fn pooled_buffer_reuse() {
+        let pool = BufferPool::new();
+        let (ptr, capacity) = {
+            let mut buffer = pool.acquire();
+            buffer.put_u64(u64::MAX).put_str("pooled");
+            (buffer.as_ptr(), buffer.capacity())
+        };
+        assert_eq!(pool.len(), 1);
+
+        let buffer = pool.acquire();
+        assert!(pool.is_empty());
+        assert!(buffer.contents().is_empty());
+        assert_eq!(buffer.pos(), 0);
+        assert_eq!(buffer.capacity(), capacity);
+        assert_eq!(buffer.as_ptr(), ptr);
+    }
+
+    #[test]
+    pub fn length_prefixed_frames() {
+        let mut bytes = Buffered::<Bytes>::new();
+        bytes.put_u32(2).put_u16(0x0102);
+        bytes.put_u32(3).put_str("hi");
+        bytes.put_u32(8).put_u8(1);
+        let mut bytes = Buffered::using(bytes.finalize());
+
+        let mut first = bytes.read_frame_u32().expect("read first frame");
+        assert_eq!(first.contents(), &[1, 2]);
+        assert_eq!(first.get_u16().expect("read first frame body"), 0x0102);
+
+        let mut second = bytes.read_frame_u32().expect("read second frame");
+        assert_eq!(second.get_str().expect("read second frame body"), "hi");
+        assert_eq!(bytes.pos(), 13);
+
+        assert!(bytes.read_frame_u32().is_err());
+        assert_eq!(bytes.pos(), 13);
+    }
+
+    #[test]
+    pub fn checked_u24_write() {
+        let mut bytes = Buffered::<Bytes>::new();
+        bytes.try_put_u24(0xFF_FFFF).expect("write max u24");
+        let err = bytes.try_put_u24(0x100_0000).expect_err("reject overflowing u24");
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+        assert_eq!(bytes.contents(), &[0xFF, 0xFF, 0xFF]);
+        assert_eq!(bytes.pos(), 3);
+    }
+
+    #[test]
+    pub fn sized_payload_read() {
+        let mut bytes: Buffered<Bytes> = Buffered::using(vec![7u8; 16].into());
+        let payload = bytes.get_bytes_vec(12).expect("read payload");
+        assert_eq!(payload, vec![7; 12]);
+        assert_eq!(payload.capacity(), 12);
+
+        let err = bytes.get_bytes_vec(usize::MAX).expect_err("reject corrupt length");
+        assert_eq!(BufferError::from_io(&err), Some(&BufferError::Eof { needed: usize::MAX, available: 4 }));
+        assert_eq!(bytes.pos(), 12);
+    }
+
+    #[test]
+    pub fn bytes_from_words() {
+        assert_eq!(&*Bytes::from_u16_be(&[0x0102, 0x0304]), &[1, 2, 3, 4]);
+        assert_eq!(&*Bytes::from_u32_be(&[0x0102_0304, 0x0506_0708]), &[1, 2, 3, 4, 5, 6, 7, 8]);
+    }
+
+    #[test]
+    pub fn retreat_by_items() {
+        let mut bytes: Buffered<Bytes> = Buffered::using(vec![1u8, 2, 3].into());
+        bytes.advance_index(2);
+        bytes.retreat_index(1).expect("retreat one item");
+        assert_eq!(bytes.index(), 1);
+        assert_eq!(bytes.get_u8().expect("read second entry"), 2);
+        assert!(bytes.retreat_index(3).is_err());
+        assert_eq!(bytes.index(), 2);
+
+        let mut words = Buffered::using(Words::new(&[1, 2, 3]));
+        words.advance_index(3);
+        words.retreat_index(2).expect("retreat two words");
+        assert_eq!(words.pos(), 2);
+    }
+
+    #[test]
+    pub fn measured_str_span() {
+        let mut bytes = Buffered::<Bytes>::new();
+        bytes.put_u16(0xFFFF);
+        let span = bytes.put_str_measured("field");
+        assert_eq!(span, 2..8);
+        assert_eq!(span.len(), "field".len() + 1);
+        assert_eq!(&bytes.contents()[span], b"field\0");
+    }
+
+    #[test]
+    pub fn content_equality_ignores_cursor() {
+        let mut written = Buffered::<Bytes>::new();
+        written.put_u16(0x0102).put_u8(3);
+        let mut read: Buffered<Bytes> = Buffered::using(vec![1u8, 2, 3].into());
+        read.get_u8().expect("read first entry");
+
+        assert_ne!(written.pos(), read.pos());
+        assert!(written.content_eq(&read));
+
+        let other: Buffered<Bytes> = Buffered::using(vec![1u8, 2].into());
+        assert!(!written.content_eq(&other));
+    }
+
+    #[test]
+    pub fn midi_vlq_examples() {
+        let examples: [(u32, &[u8]); 6] = [
+            (0x00, &[0x00]),
+            (0x7F, &[0x7F]),
+            (0x80, &[0x81, 0x00]),
+            (0x2000, &[0xC0, 0x00]),
+            (0x20_0000, &[0x81, 0x80, 0x80, 0x00]),
+            (0x0FFF_FFFF, &[0xFF, 0xFF, 0xFF, 0x7F]),
+        ];
+        for (value, encoded) in examples {
+            let mut bytes = Buffered::<Bytes>::new();
+            bytes.put_midi_vlq(value);
+            assert_eq!(bytes.contents(), encoded);
+
+            bytes.set_position(0);
+            assert_eq!(bytes.get_midi_vlq().expect("read vlq"), value);
+            assert_eq!(bytes.pos(), encoded.len());
+        }
+
+        let mut truncated: Buffered<Bytes> = Buffered::using(vec![0x81u8, 0x80].into());
+        assert!(truncated.get_midi_vlq().is_err());
+        assert_eq!(truncated.pos(), 0);
+    }
+
+    #[test]
+    pub fn index_without_size_hint() {
+        struct Opaque;
+
+        impl ToSlice for Opaque {
+            fn slice(&self, _range: std::ops::Range<usize>) -> Option<&[u8]> {
+                None
+            }
+
+            fn slice_to(&self, _range: std::ops::RangeTo<usize>) -> Option<&[u8]> {
+                None
+            }
+
+            fn len(&self) -> usize {
+                0
+            }
+        }
+
+        let opaque = Buffered::from_container(Opaque);
+        assert_eq!(opaque.try_index(), None);
+
+        let mut words = Buffered::using(Words::new(&[1, 2]));
+        words.advance_index(1);
+        assert_eq!(words.try_index(), Some(1));
+    }
+
+    #[test]
+    pub fn tuple_read() {
+        let data = vec![1u8, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07];
+        let mut tuple: Buffered<Bytes> = Buffered::using(data.clone().into());
+        let (a, b, c): (u8, u16, u32) = tuple.get_tuple().expect("read tuple");
+
+        let mut single: Buffered<Bytes> = Buffered::using(data.into());
+        assert_eq!(a, single.get_u8().expect("read u8"));
+        assert_eq!(b, single.get_u16().expect("read u16"));
+        assert_eq!(c, single.get_u32().expect("read u32"));
+        assert_eq!(tuple.pos(), single.pos());
+
+        tuple.set_position(1);
+        assert!(tuple.get_tuple::<(u16, u64)>().is_err());
+        assert_eq!(tuple.pos(), 1);
+    }
+
+    #[test]
+    pub fn checksummed_frame_round_trip() {
+        let mut bytes = Buffered::<Bytes>::new();
+        bytes.write_frame(b"123456789").write_frame(&[]);
+        let mut bytes = Buffered::using(bytes.finalize());
+        assert_eq!(bytes.len(), 25);
+
+        assert_eq!(bytes.read_frame().expect("read first frame"), b"123456789");
+        assert!(bytes.read_frame().expect("read empty frame").is_empty());
+        bytes.finish().expect("consumed both frames");
+    }
+
+    #[test]
+    pub fn checksummed_frame_corrupted() {
+        let mut bytes = Buffered::<Bytes>::new();
+        bytes.write_frame(b"payload");
+        bytes.bytes_mut()[5] ^= 0x01;
+        bytes.set_position(0);
+
+        let err = bytes.read_frame().expect_err("corrupted payload");
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert!(matches!(BufferError::from_io(&err), Some(BufferError::ChecksumMismatch { .. })));
+        assert_eq!(bytes.pos(), 0);
+    }
+
+    #[test]
+    pub fn crc32_check_value() {
+        assert_eq!(crate::bytes::composite::crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    pub fn fixed_u16_array() {
+        let mut bytes: Buffered<Bytes> = Buffered::using(Bytes::from_u16_be(&[1, 0x0203, 0xFFFF, 4]));
+        assert_eq!(bytes.get_u16_array::<3>().expect("read array"), [1, 0x0203, 0xFFFF]);
+
+        let err = bytes.get_u16_array::<2>().expect_err("short array");
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+        assert_eq!(bytes.pos(), 6);
+    }
+
+    #[test]
+    pub fn nested_saved_positions() {
+        let mut bytes: Buffered<Bytes> = Buffered::using(vec![1u8, 2, 3, 4, 5, 6].into());
+        bytes.push_pos();
+        bytes.get_u16().expect("read outer field");
+
+        bytes.push_pos();
+        bytes.get_u16().expect("read inner field");
+        bytes.pop_pos().expect("restore inner");
+        assert_eq!(bytes.pos(), 2);
+
+        bytes.push_pos();
+        bytes.get_u8().expect("read committed field");
+        bytes.discard_pos().expect("commit inner");
+        assert_eq!(bytes.pos(), 3);
+
+        bytes.pop_pos().expect("restore outer");
+        assert_eq!(bytes.pos(), 0);
+        let err = bytes.pop_pos().expect_err("empty stack");
+        assert_eq!(BufferError::from_io(&err), Some(&BufferError::NoSavedPosition));
+        assert!(bytes.discard_pos().is_err());
+    }
+
+    #[test]
+    pub fn validated_str_reports_offset() {
+        // The two-byte character straddles the boundary between validation chunks.
+        let text = format!("{}é{}", "a".repeat(4095), "b".repeat(100));
+        let mut bytes: Buffered<Bytes> = Buffered::new();
+        bytes.put_str_var(&text);
+        bytes.set_position(0);
+        assert_eq!(bytes.get_str_prefixed_validated().expect("valid string"), text);
+
+        let mut bytes: Buffered<Bytes> = Buffered::using(b"\x06abc\xffde".into());
+        let err = bytes.get_str_prefixed_validated().expect_err("invalid string");
+        assert_eq!(BufferError::from_io(&err), Some(&BufferError::InvalidUtf8 { offset: 4 }));
+        assert_eq!(bytes.pos(), 0);
+    }
+
+    #[test]
+    pub fn vec_conversions() {
+        let bytes: Buffered<Bytes> = vec![1u8, 2, 3].into();
+        assert_eq!(bytes.pos(), 0);
+        assert_eq!(bytes.contents(), &[1, 2, 3]);
+
+        let mut bytes: Buffered<Bytes> = Buffered::new();
+        bytes.put_u16(0x0102).put_u8(3);
+        let vec: Vec<u8> = bytes.into();
+        assert_eq!(vec, vec![1, 2, 3]);
+    }
+
+    #[test]
+    pub fn guard_within_catches_over_read() {
+        let mut bytes: Buffered<Bytes> = Buffered::using(vec![0u8, 1, 0, 2, 0, 3].into());
+        let err = bytes
+            .guard_within(4, |buf| {
+                buf.get_u32()?;
+                buf.get_u16()
+            })
+            .expect_err("over-read");
+        assert_eq!(
+            BufferError::from_io(&err),
+            Some(&BufferError::BoundaryExceeded { consumed: 6, max: 4 })
+        );
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(bytes.pos(), 0);
+
+        assert_eq!(bytes.guard_within(4, |buf| buf.get_u32()).expect("within bounds"), 0x0001_0002);
+        assert_eq!(bytes.pos(), 4);
+    }
+
+    #[test]
+    pub fn optional_round_trip() {
+        let mut bytes: Buffered<Bytes> = Buffered::new();
+        bytes
+            .put_optional(Some(0xDEAD_BEEFu32), |buf, value| buf.put_u32(value))
+            .put_optional(None::<u32>, |buf, value| buf.put_u32(value));
+        assert_eq!(bytes.contents(), &[1, 0xDE, 0xAD, 0xBE, 0xEF, 0]);
+
+        bytes.set_position(0);
+        assert_eq!(bytes.get_optional(|buf| buf.get_u32()).expect("some"), Some(0xDEAD_BEEF));
+        assert_eq!(bytes.get_optional(|buf| buf.get_u32()).expect("none"), None);
+
+        let mut bytes: Buffered<Bytes> = Buffered::using(vec![2u8, 0].into());
+        let err = bytes.get_optional(|buf| buf.get_u8()).expect_err("bad discriminant");
+        assert_eq!(BufferError::from_io(&err), Some(&BufferError::InvalidDiscriminant { value: 2 }));
+        assert_eq!(bytes.pos(), 0);
+    }
+
+    #[test]
+    pub fn little_endian_floats() {
+        let mut bytes: Buffered<Bytes> = Buffered::new();
+        bytes.put_f32_le(1.5).put_f64_le(-0.25);
+        assert_eq!(&bytes.contents()[..4], &[0x00, 0x00, 0xC0, 0x3F]);
+        assert_ne!(&bytes.contents()[..4], &1.5f32.to_be_bytes());
+        assert_eq!(&bytes.contents()[4..], &(-0.25f64).to_le_bytes());
+        assert_ne!(&bytes.contents()[4..], &(-0.25f64).to_be_bytes());
+
+        bytes.set_position(0);
+        assert_eq!(bytes.get_f32_le().expect("read f32"), 1.5);
+        assert_eq!(bytes.get_f64_le().expect("read f64"), -0.25);
+        assert!(bytes.get_f32_le().is_err());
+    }
+
+    #[test]
+    pub fn str_list() {
+        let mut bytes: Buffered<Bytes> = Buffered::using(b"alpha\0beta\0gamma\0".into());
+        assert_eq!(bytes.get_str_list().expect("read list"), vec!["alpha", "beta", "gamma"]);
+        assert_eq!(bytes.remaining(), 0);
+
+        let mut bytes: Buffered<Bytes> = Buffered::using(b"one\0two\0\0\x7F".into());
+        assert_eq!(bytes.get_str_list_terminated().expect("read list"), vec!["one", "two"]);
+        assert_eq!(bytes.get_u8().expect("read trailer"), 0x7F);
+
+        let mut bytes: Buffered<Bytes> = Buffered::using(b"one\0two".into());
+        assert!(bytes.get_str_list().is_err());
+        assert_eq!(bytes.pos(), 0);
+    }
+
+    #[test]
+    pub fn str_list_round_trip() {
+        let items = ["first", "", "third"];
+        let mut bytes: Buffered<Bytes> = Buffered::new();
+        bytes.put_str_list(&items);
+        assert_eq!(bytes.contents(), b"first\0\0third\0");
+        bytes.set_position(0);
+        assert_eq!(bytes.get_str_list().expect("read list"), items);
+
+        let mut bytes: Buffered<Bytes> = Buffered::new();
+        bytes.put_str_list_terminated(&["a", "b"]).put_u8(0x7F);
+        bytes.set_position(0);
+        assert_eq!(bytes.get_str_list_terminated().expect("read list"), vec!["a", "b"]);
+        assert_eq!(bytes.get_u8().expect("read trailer"), 0x7F);
+    }
+
+    #[test]
+    pub fn encoded_lengths() {
+        use crate::bytes::encoded_len::{frame_len, str_len, str_var_len, var_u32_len};
+
+        assert_eq!(var_u32_len(0), 1);
+        assert_eq!(var_u32_len(127), 1);
+        assert_eq!(var_u32_len(300), 2);
+        assert_eq!(var_u32_len(u32::MAX), 5);
+        assert_eq!(str_len("hi"), 3);
+
+        let mut bytes: Buffered<Bytes> = Buffered::new();
+        bytes.put_str_var("hello").write_frame(b"abc");
+        assert_eq!(bytes.contents().len(), str_var_len("hello") + frame_len(3));
+    }
+
+    #[test]
+    pub fn tap_receives_consumed_bytes() {
+        use std::sync::{Arc, Mutex};
+
+        let tapped = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&tapped);
+        let mut bytes: Buffered<Bytes> = Buffered::using(b"\x01\x00\x02hi\0\xAC\x02\x09".into());
+        bytes.with_tap(move |consumed| sink.lock().unwrap().push(consumed.to_vec()));
+
+        assert_eq!(bytes.get_u8().expect("read u8"), 1);
+        assert_eq!(bytes.get_u16().expect("read u16"), 2);
+        assert_eq!(bytes.get_str().expect("read str"), "hi");
+        assert_eq!(bytes.get_var_u32().expect("read varint"), 300);
+        assert!(bytes.get_u16().is_err());
+        bytes.clear_tap();
+        assert_eq!(bytes.get_u8().expect("read untapped"), 9);
+
+        let tapped = tapped.lock().unwrap();
+        assert_eq!(*tapped, vec![vec![1], vec![0, 2], b"hi\0".to_vec(), vec![0xAC, 0x02]]);
+    }
+
+    #[test]
+    pub fn failed_reads_leave_position_unchanged() {
+        type Read = fn(&mut Buffered<Bytes>) -> bool;
+
+        // Two bytes remain, each with its continuation bit set and neither a null terminator.
+        let short: &[(&str, Read)] = &[
+            ("get_u24", |b| b.get_u24().is_err()),
+            ("get_i24_le", |b| b.get_i24_le().is_err()),
+            ("get_i32", |b| b.get_i32().is_err()),
+            ("get_u32", |b| b.get_u32().is_err()),
+            ("get_i64", |b| b.get_i64().is_err()),
+            ("get_u64", |b| b.get_u64().is_err()),
+            ("get_f32_le", |b| b.get_f32_le().is_err()),
+            ("get_f64_le", |b| b.get_f64_le().is_err()),
+            ("get_i8_slice", |b| b.get_i8_slice(3).is_err()),
+            ("get_u16_array", |b| b.get_u16_array::<2>().is_err()),
+            ("get_f32_slice", |b| b.get_f32_slice(1).is_err()),
+            ("get_f64_slice", |b| b.get_f64_slice(1).is_err()),
+            ("get_exact", |b| b.get_exact(3).is_err()),
+            ("get_bytes_vec", |b| b.get_bytes_vec(3).is_err()),
+            ("get_tuple", |b| b.get_tuple::<(u8, u16)>().is_err()),
+            ("read_frame_u32", |b| b.read_frame_u32().is_err()),
+            ("read_frame", |b| b.read_frame().is_err()),
+            ("get_str", |b| b.get_str().is_err()),
+            ("get_cstr_bytes", |b| b.get_cstr_bytes().is_err()),
+            ("get_str_list", |b| b.get_str_list().is_err()),
+            ("get_str_list_terminated", |b| b.get_str_list_terminated().is_err()),
+            ("get_var_u32", |b| b.get_var_u32().is_err()),
+            ("get_midi_vlq", |b| b.get_midi_vlq().is_err()),
+            ("get_str_var", |b| b.get_str_var().is_err()),
+            ("get_str_prefixed_validated", |b| b.get_str_prefixed_validated().is_err()),
+            ("get_optional", |b| b.get_optional(|b| b.get_u8()).is_err()),
+        ];
+        for (name, read) in short {
+            let mut bytes: Buffered<Bytes> = Buffered::using(vec![0xFFu8, 0x81, 0x81].into());
+            bytes.set_position(1);
+            assert!(read(&mut bytes), "{} should fail", name);
+            assert_eq!(bytes.pos(), 1, "{} moved the cursor", name);
+        }
+
+        let empty: &[(&str, Read)] = &[
+            ("get_u8", |b| b.get_u8().is_err()),
+            ("get_i8", |b| b.get_i8().is_err()),
+            ("get_u16", |b| b.get_u16().is_err()),
+            ("get_i16", |b| b.get_i16().is_err()),
+            ("get_flags8", |b| b.get_flags8().is_err()),
+        ];
+        for (name, read) in empty {
+            let mut bytes: Buffered<Bytes> = Buffered::using(vec![0xFFu8].into());
+            bytes.set_position(1);
+            assert!(read(&mut bytes), "{} should fail", name);
+            assert_eq!(bytes.pos(), 1, "{} moved the cursor", name);
+        }
+
+        // Failures after part of the input has been decoded.
+        let mut bytes: Buffered<Bytes> = Buffered::using(b"\x05ab".into());
+        assert!(bytes.get_str_var().is_err());
+        assert_eq!(bytes.pos(), 0);
+        let mut bytes: Buffered<Bytes> = Buffered::using(b"ok\0\xFF\0".into());
+        assert!(bytes.get_str_list().is_err());
+        assert_eq!(bytes.pos(), 0);
+    }
+
+    #[test]
+    pub fn checked_section() {
+        let mut bytes: Buffered<Bytes> = Buffered::new();
+        bytes.write_checked_section_u32(b"123456789").put_u8(0x7F);
+        assert_eq!(&bytes.contents()[13..17], &0xCBF4_3926u32.to_be_bytes());
+
+        bytes.set_position(0);
+        let mut section = bytes.read_checked_section_u32().expect("read section");
+        assert_eq!(section.contents(), b"123456789");
+        assert_eq!(section.get_u8().expect("read payload"), b'1');
+        assert_eq!(bytes.get_u8().expect("read trailer"), 0x7F);
+
+        bytes.bytes_mut()[6] ^= 0xFF;
+        bytes.set_position(0);
+        let err = bytes.read_checked_section_u32().expect_err("corrupt section");
+        assert!(matches!(
+            BufferError::from_io(&err),
+            Some(BufferError::ChecksumMismatch { expected: 0xCBF4_3926, .. })
+        ));
+        assert_eq!(bytes.pos(), 0);
+    }
+
+    #[test]
+    pub fn last_span_tracks_fields() {
+        let mut bytes: Buffered<Bytes> = Buffered::new();
+        assert_eq!(bytes.last_span(), None);
+        bytes.put_u8(7);
+        assert_eq!(bytes.last_span(), Some(0..1));
+        bytes.put_str("abc");
+        assert_eq!(bytes.last_span(), Some(1..5));
+        bytes.put_u32(0xDEAD_BEEF);
+        assert_eq!(bytes.last_span(), Some(5..9));
+
+        bytes.set_position(5);
+        let pos_before = bytes.pos();
+        assert_eq!(bytes.get_u32().expect("read u32"), 0xDEAD_BEEF);
+        assert_eq!(bytes.last_span(), Some(pos_before..pos_before + 4));
+
+        bytes.set_position(1);
+        bytes.get_str().expect("read str");
+        assert_eq!(bytes.last_span(), Some(1..5));
+        bytes.set_position(bytes.len());
+        assert!(bytes.get_u8().is_err());
+        assert_eq!(bytes.last_span(), Some(1..5));
+
+        // A composite read which fails after its inner reads succeed leaves the span untouched too.
+        let mut bytes: Buffered<Bytes> = Buffered::using(b"\x05ab".into());
+        assert!(bytes.get_str_var().is_err());
+        assert_eq!(bytes.last_span(), None);
+    }
+
+    #[test]
+    pub fn reads_stop_at_written_data() {
+        let mut bytes: Buffered<Bytes> = Buffered::new();
+        bytes.put_u16(1).put_u8(2);
+        assert!(bytes.bytes().len() > 3);
+        assert_eq!(bytes.remaining(), 0);
+
+        bytes.set_position(0);
+        assert_eq!(bytes.get_u16().expect("read u16"), 1);
+        assert_eq!(bytes.get_u8().expect("read u8"), 2);
+        assert!(bytes.get_u8().is_err());
+        bytes.finish().expect("no trailing bytes");
+    }
+
+    #[test]
+    pub fn put_buffered_past_end() {
+        let mut bytes: Buffered<Bytes> = Buffered::new();
+
+        let mut other: Buffered<Bytes> = Buffered::using(vec![1u8, 2, 3].into());
+        other.set_position(10);
+        bytes.put_buffered(&mut other);
+        assert_eq!(other.pos(), 10);
+
+        let mut other: Buffered<Bytes> = Buffered::using(vec![1u8, 2, 3].into());
+        other.set_position(3);
+        other.limit(2);
+        bytes.put_buffered(&mut other);
+        assert_eq!(other.pos(), 3);
+        assert_eq!(bytes.pos(), 0);
+    }
+
+    #[test]
+    pub fn stale_var_len_placeholder() {
+        let mut bytes: Buffered<Bytes> = Buffered::new();
+        bytes.put_u32(0).put_u32(0).put_u32(0).put_u32(0);
+        let placeholder = bytes.reserve_var_len();
+        bytes.clear();
+        let err = bytes.patch_var_len(placeholder).expect_err("stale slot");
+        assert_eq!(
+            BufferError::from_io(&err),
+            Some(&BufferError::SlotOutOfBounds { pos: 16, len: 0 })
+        );
+    }
+
+    #[test]
+    pub fn try_get_at_logical_end() {
+        let mut bytes: Buffered<Bytes> = Buffered::using(vec![0u8, 1, 2, 3].into());
+        bytes.limit(2);
+        assert!(matches!(bytes.try_get_u32(), DecodeResult::Error(_)));
+        assert!(matches!(bytes.try_get_u16(), DecodeResult::Complete(1)));
+        assert!(matches!(bytes.try_get_u8(), DecodeResult::Error(_)));
+
+        let mut bytes: Buffered<Bytes> = Buffered::using(vec![0u8, 1].into());
+        bytes.limit(8);
+        assert!(bytes.try_get_u32().is_incomplete());
+    }
+
+    #[test]
+    pub fn pooled_buffer_resets_alloc_limit() {
+        let pool = BufferPool::new();
+        pool.acquire().set_alloc_limit(1);
+        assert_eq!(pool.acquire().alloc_limit(), None);
+    }
+
+    #[test]
+    pub fn pooled_buffer_drops_tap() {
+        use std::sync::{Arc, Mutex};
+
+        let pool = BufferPool::new();
+        let tapped = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&tapped);
+        pool.acquire().with_tap(move |consumed| sink.lock().unwrap().extend_from_slice(consumed));
+
+        let mut buffer = pool.acquire();
+        buffer.put_u32(0xAABB_CCDD);
+        buffer.set_position(0);
+        assert_eq!(buffer.get_u32().expect("read u32"), 0xAABB_CCDD);
+        assert!(tapped.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    pub fn buffered_is_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Buffered<Bytes>>();
+    }
+
+    #[test]
+    pub fn deref_excludes_padding() {
+        let mut bytes: Buffered<Bytes> = Buffered::new();
+        bytes.put_u32(1).put_u8(2);
+        let view: &[u8] = bytes.get_inner();
+        assert_eq!(view, &[0, 0, 0, 1, 2]);
+        assert_eq!(view.len(), ToSlice::len(bytes.get_inner()));
+
+        let inner = bytes.into_inner();
+        assert_eq!(inner.to_vec(), vec![0, 0, 0, 1, 2]);
+    }
+
+    #[test]
+    pub fn peek_written_contents() {
+        let mut bytes: Buffered<Bytes> = Buffered::new();
+        bytes.put_u32(1).put_u8(2);
+        assert_eq!(bytes.peek_bytes_at(3, 2), Some(&[1u8, 2][..]));
+        assert_eq!(bytes.peek_bytes_at(5, 2), None);
+    }
+
+    #[test]
+    #[cfg(feature = "zeroize")]
+    pub fn zeroize_growth_keeps_contents() {
+        let mut bytes: Buffered<Bytes> = Buffered::new();
+        for value in 0..100u8 {
+            bytes.put_u8(value);
+        }
+        bytes.insert(0, &[0xFF; 64]);
+        assert_eq!(&bytes.contents()[..64], &[0xFF; 64][..]);
+        assert!(bytes.contents()[64..].iter().copied().eq(0..100u8));
+    }
+
+    #[test]
+    pub fn table_entry_within_written_contents() {
+        let mut bytes: Buffered<Bytes> = Buffered::new();
+        bytes.put_u32(1).put_u8(2);
+        assert_eq!(bytes.read_u32_at_index(0, 0).expect("read entry"), 1);
+        let err = bytes.read_u32_at_index(4, 0).expect_err("entry in padding");
+        assert_eq!(
+            BufferError::from_io(&err),
+            Some(&BufferError::Eof { needed: 4, available: 1 })
+        );
+    }
+
+    #[test]
+    pub fn tap_receives_skipped_bytes() {
+        use std::sync::{Arc, Mutex};
+
+        let tapped = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&tapped);
+        let mut bytes: Buffered<Bytes> = Buffered::using(b"123x--;y".into());
+        bytes.with_tap(move |consumed| sink.lock().unwrap().extend_from_slice(consumed));
+
+        assert_eq!(bytes.take_while(|byte| byte.is_ascii_digit()), b"123");
+        assert_eq!(bytes.get_u8().expect("read marker"), b'x');
+        bytes.seek_to_byte(b';').expect("seek to separator");
+        assert!(bytes.seek_to_byte(b'!').is_err());
+        assert_eq!(bytes.get_u8().expect("read separator"), b';');
+        assert_eq!(*tapped.lock().unwrap(), b"123x--;".to_vec());
+    }
+}
+
+/// Exercises the core buffer API through `core` and `alloc` alone, as a `no_std` consumer would. Run with
+/// `--no-default-features` to check the build without `std`.
+#[cfg(test)]
+mod alloc_tests {
+    use alloc::{string::String, vec};
+
+    use crate::{Buffered, bytes::Bytes, error::{buffer_error, BufferError}};
+
+    #[test]
+    pub fn basic_read_write() {
+        let mut bytes = Buffered::<Bytes>::new();
+        bytes.put_u8(1).put_u32(0xDEAD_BEEF).put_str("alloc").put_var_u32(300);
+        bytes.set_position(0);
+
+        assert_eq!(bytes.get_u8().expect("read u8"), 1);
+        assert_eq!(bytes.get_u32().expect("read u32"), 0xDEAD_BEEF);
+        assert_eq!(bytes.get_str().expect("read str"), String::from("alloc"));
+        assert_eq!(bytes.get_var_u32().expect("read varint"), 300);
+    }
+
+    #[test]
+    pub fn eof_without_std() {
+        let mut bytes: Buffered<Bytes> = Buffered::using(vec![1u8, 2].into());
+        let err = bytes.get_u32().expect_err("short read");
+        assert_eq!(buffer_error(&err), Some(&BufferError::Eof { needed: 4, available: 2 }));
+        assert_eq!(bytes.pos(), 0);
+    }
+}
@@ -0,0 +1,73 @@
+use std::{
+    ops::{Deref, DerefMut},
+    sync::{Mutex, MutexGuard, PoisonError},
+};
+
+use crate::{bytes::Bytes, Buffered};
+
+/// A pool of reusable byte buffers. Buffers handed out by [acquire](BufferPool::acquire) are [cleared](Buffered::clear)
+/// and returned to the pool when their guard is dropped, so their allocations are reused rather than freed.
+#[derive(Debug, Default)]
+pub struct BufferPool {
+    buffers: Mutex<Vec<Buffered<Bytes>>>,
+}
+
+impl BufferPool {
+    /// Constructs a new, empty pool.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Takes an idle buffer from the pool, or creates a new one if none are available.
+    pub fn acquire(&self) -> PooledBuffer<'_> {
+        let buffer = self.lock().pop().unwrap_or_default();
+        PooledBuffer {
+            pool: self,
+            buffer: Some(buffer),
+        }
+    }
+
+    /// Returns the number of idle buffers held by the pool.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Returns `true` if the pool holds no idle buffers.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<Buffered<Bytes>>> {
+        self.buffers.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// A buffer on loan from a [BufferPool], returned to the pool when dropped.
+#[derive(Debug)]
+pub struct PooledBuffer<'a> {
+    pool: &'a BufferPool,
+    buffer: Option<Buffered<Bytes>>,
+}
+
+impl Deref for PooledBuffer<'_> {
+    type Target = Buffered<Bytes>;
+
+    fn deref(&self) -> &Self::Target {
+        self.buffer.as_ref().expect("buffer is present until dropped")
+    }
+}
+
+impl DerefMut for PooledBuffer<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.buffer.as_mut().expect("buffer is present until dropped")
+    }
+}
+
+impl Drop for PooledBuffer<'_> {
+    fn drop(&mut self) {
+        if let Some(mut buffer) = self.buffer.take() {
+            buffer.clear();
+            self.pool.lock().push(buffer);
+        }
+    }
+}